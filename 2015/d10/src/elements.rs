@@ -0,0 +1,318 @@
+use std::ops::AddAssign;
+
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::{iter_n_times, next};
+
+// Conway's cosmological theorem: after at most 24 steps every string is a
+// compound of common and transuranic elements.
+const MAX_DECAY_STEPS: usize = 24;
+
+// How many generations of the right side of a boundary we follow (and how many
+// of its leading digits we keep) to decide that both sides never interact.
+const SPLIT_HORIZON: usize = 16;
+const SPLIT_PREFIX: usize = 32;
+
+pub struct Element {
+    pub name: &'static str,
+    pub seq: &'static str,
+    // atomic numbers of the elements this one decays into
+    pub decay: &'static [usize],
+}
+
+#[rustfmt::skip]
+pub const ELEMENTS: [Element; 92] = [
+    Element { name: "H", seq: "22", decay: &[1] },
+    Element { name: "He", seq: "13112221133211322112211213322112", decay: &[72, 91, 1, 20, 3] },
+    Element { name: "Li", seq: "312211322212221121123222112", decay: &[2] },
+    Element { name: "Be", seq: "111312211312113221133211322112211213322112", decay: &[32, 20, 3] },
+    Element { name: "B", seq: "1321132122211322212221121123222112", decay: &[4] },
+    Element { name: "C", seq: "3113112211322112211213322112", decay: &[5] },
+    Element { name: "N", seq: "111312212221121123222112", decay: &[6] },
+    Element { name: "O", seq: "132112211213322112", decay: &[7] },
+    Element { name: "F", seq: "31121123222112", decay: &[8] },
+    Element { name: "Ne", seq: "111213322112", decay: &[9] },
+    Element { name: "Na", seq: "123222112", decay: &[10] },
+    Element { name: "Mg", seq: "3113322112", decay: &[61, 11] },
+    Element { name: "Al", seq: "1113222112", decay: &[12] },
+    Element { name: "Si", seq: "1322112", decay: &[13] },
+    Element { name: "P", seq: "311311222112", decay: &[67, 14] },
+    Element { name: "S", seq: "1113122112", decay: &[15] },
+    Element { name: "Cl", seq: "132112", decay: &[16] },
+    Element { name: "Ar", seq: "3112", decay: &[17] },
+    Element { name: "K", seq: "1112", decay: &[18] },
+    Element { name: "Ca", seq: "12", decay: &[19] },
+    Element { name: "Sc", seq: "3113112221133112", decay: &[67, 91, 1, 20, 27] },
+    Element { name: "Ti", seq: "11131221131112", decay: &[21] },
+    Element { name: "V", seq: "13211312", decay: &[22] },
+    Element { name: "Cr", seq: "31132", decay: &[23] },
+    Element { name: "Mn", seq: "111311222112", decay: &[24, 14] },
+    Element { name: "Fe", seq: "13122112", decay: &[25] },
+    Element { name: "Co", seq: "32112", decay: &[26] },
+    Element { name: "Ni", seq: "11133112", decay: &[30, 27] },
+    Element { name: "Cu", seq: "131112", decay: &[28] },
+    Element { name: "Zn", seq: "312", decay: &[29] },
+    Element { name: "Ga", seq: "13221133122211332", decay: &[63, 20, 89, 1, 20, 30] },
+    Element { name: "Ge", seq: "31131122211311122113222", decay: &[67, 31] },
+    Element { name: "As", seq: "11131221131211322113322112", decay: &[32, 11] },
+    Element { name: "Se", seq: "13211321222113222112", decay: &[33] },
+    Element { name: "Br", seq: "3113112211322112", decay: &[34] },
+    Element { name: "Kr", seq: "11131221222112", decay: &[35] },
+    Element { name: "Rb", seq: "1321122112", decay: &[36] },
+    Element { name: "Sr", seq: "3112112", decay: &[37] },
+    Element { name: "Y", seq: "1112133", decay: &[38, 92] },
+    Element { name: "Zr", seq: "12322211331222113112211", decay: &[39, 1, 20, 43] },
+    Element { name: "Nb", seq: "1113122113322113111221131221", decay: &[68, 40] },
+    Element { name: "Mo", seq: "13211322211312113211", decay: &[41] },
+    Element { name: "Tc", seq: "311322113212221", decay: &[42] },
+    Element { name: "Ru", seq: "132211331222113112211", decay: &[63, 20, 43] },
+    Element { name: "Rh", seq: "311311222113111221131221", decay: &[67, 44] },
+    Element { name: "Pd", seq: "111312211312113211", decay: &[45] },
+    Element { name: "Ag", seq: "132113212221", decay: &[46] },
+    Element { name: "Cd", seq: "3113112211", decay: &[47] },
+    Element { name: "In", seq: "11131221", decay: &[48] },
+    Element { name: "Sn", seq: "13211", decay: &[49] },
+    Element { name: "Sb", seq: "3112221", decay: &[61, 50] },
+    Element { name: "Te", seq: "1322113312211", decay: &[63, 20, 51] },
+    Element { name: "I", seq: "311311222113111221", decay: &[67, 52] },
+    Element { name: "Xe", seq: "11131221131211", decay: &[53] },
+    Element { name: "Cs", seq: "13211321", decay: &[54] },
+    Element { name: "Ba", seq: "311311", decay: &[55] },
+    Element { name: "La", seq: "11131", decay: &[56] },
+    Element { name: "Ce", seq: "1321133112", decay: &[57, 1, 20, 27] },
+    Element { name: "Pr", seq: "31131112", decay: &[58] },
+    Element { name: "Nd", seq: "111312", decay: &[59] },
+    Element { name: "Pm", seq: "132", decay: &[60] },
+    Element { name: "Sm", seq: "311332", decay: &[61, 20, 30] },
+    Element { name: "Eu", seq: "1113222", decay: &[62] },
+    Element { name: "Gd", seq: "13221133112", decay: &[63, 20, 27] },
+    Element { name: "Tb", seq: "3113112221131112", decay: &[67, 64] },
+    Element { name: "Dy", seq: "111312211312", decay: &[65] },
+    Element { name: "Ho", seq: "1321132", decay: &[66] },
+    Element { name: "Er", seq: "311311222", decay: &[67, 61] },
+    Element { name: "Tm", seq: "11131221133112", decay: &[68, 20, 27] },
+    Element { name: "Yb", seq: "1321131112", decay: &[69] },
+    Element { name: "Lu", seq: "311312", decay: &[70] },
+    Element { name: "Hf", seq: "11132", decay: &[71] },
+    Element { name: "Ta", seq: "13112221133211322112211213322113", decay: &[72, 91, 1, 20, 74] },
+    Element { name: "W", seq: "312211322212221121123222113", decay: &[73] },
+    Element { name: "Re", seq: "111312211312113221133211322112211213322113", decay: &[32, 20, 74] },
+    Element { name: "Os", seq: "1321132122211322212221121123222113", decay: &[75] },
+    Element { name: "Ir", seq: "3113112211322112211213322113", decay: &[76] },
+    Element { name: "Pt", seq: "111312212221121123222113", decay: &[77] },
+    Element { name: "Au", seq: "132112211213322113", decay: &[78] },
+    Element { name: "Hg", seq: "31121123222113", decay: &[79] },
+    Element { name: "Tl", seq: "111213322113", decay: &[80] },
+    Element { name: "Pb", seq: "123222113", decay: &[81] },
+    Element { name: "Bi", seq: "3113322113", decay: &[61, 82] },
+    Element { name: "Po", seq: "1113222113", decay: &[83] },
+    Element { name: "At", seq: "1322113", decay: &[84] },
+    Element { name: "Rn", seq: "311311222113", decay: &[67, 85] },
+    Element { name: "Fr", seq: "1113122113", decay: &[86] },
+    Element { name: "Ra", seq: "132113", decay: &[87] },
+    Element { name: "Ac", seq: "3113", decay: &[88] },
+    Element { name: "Th", seq: "1113", decay: &[89] },
+    Element { name: "Pa", seq: "13", decay: &[90] },
+    Element { name: "U", seq: "3", decay: &[91] },
+];
+
+pub fn find_element(seq: &[u8]) -> Option<usize> {
+    ELEMENTS
+        .iter()
+        .position(|e| e.seq.bytes().map(|b| b - b'0').eq(seq.iter().copied()))
+}
+
+fn last_run_start(digits: &[u8]) -> usize {
+    let last = digits[digits.len() - 1];
+    digits.iter().rposition(|&d| d != last).map_or(0, |i| i + 1)
+}
+
+// LR splits into L.R iff the last digit of L (which never changes) never meets
+// the first digit of any descendant of R.
+fn splits_at(digits: &[u8], i: usize) -> bool {
+    let last = digits[i - 1];
+    let truncated = digits.len() - i > SPLIT_PREFIX;
+    let mut right = digits[i..]
+        .iter()
+        .take(SPLIT_PREFIX)
+        .copied()
+        .collect::<Vec<u8>>();
+
+    for _ in 0..SPLIT_HORIZON {
+        if right[0] == last {
+            return false;
+        }
+        if truncated {
+            // the last run may continue past the prefix, so its count is unknown
+            right.truncate(last_run_start(&right));
+            if right.is_empty() {
+                break;
+            }
+        }
+        right = next(&right);
+    }
+
+    true
+}
+
+pub fn split(digits: &[u8]) -> Vec<&[u8]> {
+    let mut atoms = Vec::new();
+    let mut start = 0;
+
+    for i in 1..digits.len() {
+        if digits[i] != digits[i - 1] && splits_at(digits, i) {
+            atoms.push(&digits[start..i]);
+            start = i;
+        }
+    }
+    atoms.push(&digits[start..]);
+
+    atoms
+}
+
+fn decay<T>(counts: &[T]) -> Vec<T>
+where
+    T: Clone + Zero + for<'a> AddAssign<&'a T>,
+{
+    let mut out = vec![T::zero(); ELEMENTS.len()];
+    for (elem, count) in ELEMENTS.iter().zip(counts) {
+        for &z in elem.decay {
+            out[z - 1] += count;
+        }
+    }
+    out
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Compound(Vec<BigUint>);
+
+impl Compound {
+    pub fn decompose(digits: &[u8]) -> Option<Compound> {
+        let mut counts = vec![BigUint::zero(); ELEMENTS.len()];
+        for atom in split(digits) {
+            counts[find_element(atom)?] += 1u8;
+        }
+        Some(Compound(counts))
+    }
+
+    pub fn next(&self) -> Compound {
+        Compound(decay(&self.0))
+    }
+
+    pub fn iter_n_times(self, n: usize) -> Compound {
+        (0..n).fold(self, |compound, _| compound.next())
+    }
+
+    pub fn len(&self) -> BigUint {
+        ELEMENTS
+            .iter()
+            .zip(&self.0)
+            .map(|(elem, count)| count * elem.seq.len())
+            .sum()
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = (&'static Element, &BigUint)> {
+        ELEMENTS
+            .iter()
+            .zip(&self.0)
+            .filter(|(_, count)| !count.is_zero())
+    }
+
+    // Ratio between the lengths of the (n + 1)-th and n-th descendants,
+    // which tends to Conway's constant.
+    pub fn growth_rate(&self, n: usize) -> f64 {
+        let lens: Vec<f64> = ELEMENTS.iter().map(|e| e.seq.len() as f64).collect();
+        let total = |counts: &[f64]| counts.iter().zip(&lens).map(|(c, l)| c * l).sum::<f64>();
+
+        let mut counts: Vec<f64> = self.0.iter().map(|c| c.to_f64().unwrap()).collect();
+        for _ in 0..n {
+            let len = total(&counts);
+            counts = decay(&counts).into_iter().map(|c| c / len).collect();
+        }
+
+        total(&decay(&counts)) / total(&counts)
+    }
+}
+
+// Materializes the first few steps until the sequence is a compound of
+// common elements, returning that compound and how many steps it took.
+pub fn decay_fully(digits: &[u8]) -> Option<(Compound, usize)> {
+    let mut digits = digits.to_vec();
+    for step in 0..=MAX_DECAY_STEPS {
+        if let Some(compound) = Compound::decompose(&digits) {
+            return Some((compound, step));
+        }
+        digits = next(&digits);
+    }
+    None
+}
+
+// Takes what `decay_fully` made of `digits`.
+pub fn length_after(digits: &[u8], (compound, steps): &(Compound, usize), n: usize) -> BigUint {
+    if n < *steps {
+        iter_n_times(digits.to_vec(), n).len().into()
+    } else {
+        compound.clone().iter_n_times(n - steps).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_digits;
+
+    fn parse(s: &str) -> Vec<u8> {
+        parse_digits(s).unwrap()
+    }
+
+    #[test]
+    fn test_elements_decay_into_their_products() {
+        for elem in ELEMENTS.iter() {
+            let digits = parse(elem.seq);
+            let products: String = elem.decay.iter().map(|&z| ELEMENTS[z - 1].seq).collect();
+            assert_eq!(next(&digits), parse(&products), "{}", elem.name);
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let digits = parse("132123222113");
+        assert_eq!(split(&digits), vec![&digits[..3], &digits[3..]]);
+        assert_eq!(split(&parse("1")).len(), 1);
+    }
+
+    #[test]
+    fn test_decompose() {
+        let compound = Compound::decompose(&parse("1113122113")).unwrap();
+        assert_eq!(
+            compound
+                .elements()
+                .map(|(e, c)| (e.name, c.clone()))
+                .collect::<Vec<_>>(),
+            vec![("Fr", BigUint::from(1u8))]
+        );
+        assert_eq!(Compound::decompose(&parse("1")), None);
+    }
+
+    #[test]
+    fn test_length_after() {
+        for seed in ["1", "3", "1113122113", "2"] {
+            let digits = parse(seed);
+            let decayed = decay_fully(&digits).unwrap();
+            for n in [0, 5, 10, 30] {
+                assert_eq!(
+                    length_after(&digits, &decayed, n),
+                    BigUint::from(iter_n_times(digits.clone(), n).len()),
+                );
+            }
+        }
+        assert!(decay_fully(&parse("4")).is_none());
+    }
+
+    #[test]
+    fn test_growth_rate() {
+        let (compound, _) = decay_fully(&parse("1")).unwrap();
+        assert!((compound.growth_rate(200) - 1.303577269).abs() < 1e-9);
+    }
+}
@@ -2,52 +2,53 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
-fn next(prev: String) -> String {
-    let mut out = String::with_capacity(2 * prev.len());
-
-    let mut count = 0;
-    let mut curr = None;
-
-    for c in prev.chars() {
-        match curr {
-            Some(curr_c) if curr_c == c => {
-                count += 1;
-            }
-            Some(curr_c) => {
-                out.push_str(format!("{}", count).as_ref());
-                out.push(curr_c);
-
-                curr = Some(c);
-                count = 1;
-            }
-            None => {
-                curr = Some(c);
-                count += 1;
-            }
-        }
+mod elements;
+
+type Digits = Vec<u8>;
+
+// Fails on the first character that is not a digit, surrounding whitespace
+// aside.
+fn parse_digits(s: &str) -> Result<Digits, char> {
+    s.trim()
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(c))
+        .collect()
+}
+
+fn push_count(out: &mut Digits, count: usize) {
+    if count >= 10 {
+        push_count(out, count / 10);
     }
+    out.push((count % 10) as u8);
+}
+
+fn next(prev: &[u8]) -> Digits {
+    let mut out = Digits::with_capacity(2 * prev.len());
 
-    out.push_str(format!("{}", count).as_ref());
-    out.push(curr.unwrap());
+    for run in prev.chunk_by(|a, b| a == b) {
+        push_count(&mut out, run.len());
+        out.push(run[0]);
+    }
 
     out
 }
 
-fn iter_n_times(input: String, n: usize) -> String {
+fn iter_n_times(input: Digits, n: usize) -> Digits {
     let mut res = input;
 
     for _ in 0..n {
-        res = next(res);
+        res = next(&res);
     }
 
     res
 }
 
-fn read_input<P>(path: P) -> String
+fn read_input<P>(path: P) -> Digits
 where
     P: AsRef<Path>,
 {
-    fs::read_to_string(path).unwrap()
+    parse_digits(&fs::read_to_string(path).unwrap())
+        .unwrap_or_else(|c| panic!("Unexpected '{}' in the input", c))
 }
 
 fn part1() {
@@ -60,12 +61,33 @@ fn part2() {
     println!("{}", iter_n_times(input, 50).len());
 }
 
+fn conway(n: usize) {
+    let input = read_input("input.txt");
+    match elements::decay_fully(&input) {
+        Some(decayed) => {
+            let (compound, steps) = &decayed;
+            let atoms: Vec<String> = compound
+                .elements()
+                .map(|(elem, count)| format!("{}x{}", count, elem.name))
+                .collect();
+            println!("after {} steps: {}", steps, atoms.join(" "));
+            println!("{}", elements::length_after(&input, &decayed, n));
+            println!(
+                "growth rate: {}",
+                compound.growth_rate(n.saturating_sub(*steps))
+            );
+        }
+        None => println!("Input does not decay into common elements."),
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "elements" => conway(args.get(2).map_or(1000, |n| n.parse().unwrap())),
+        _ => println!("Must pass either '1', '2' or 'elements [n]'."),
     }
 }
 
@@ -73,12 +95,24 @@ fn main() {
 mod tests {
     use super::*;
 
+    fn parse(s: &str) -> Digits {
+        parse_digits(s).unwrap()
+    }
+
     #[test]
     fn test_next() {
-        assert_eq!(next("1".to_string()), "11".to_string());
-        assert_eq!(next("11".to_string()), "21".to_string());
-        assert_eq!(next("21".to_string()), "1211".to_string());
-        assert_eq!(next("1211".to_string()), "111221".to_string());
-        assert_eq!(next("111221".to_string()), "312211".to_string());
+        assert_eq!(next(&parse("1")), parse("11"));
+        assert_eq!(next(&parse("11")), parse("21"));
+        assert_eq!(next(&parse("21")), parse("1211"));
+        assert_eq!(next(&parse("1211")), parse("111221"));
+        assert_eq!(next(&parse("111221")), parse("312211"));
+        assert_eq!(next(&parse("1111111111")), parse("101"));
+    }
+
+    #[test]
+    fn test_parse_digits() {
+        assert_eq!(parse_digits("1321\r\n"), Ok(vec![1, 3, 2, 1]));
+        assert_eq!(parse_digits("13a1"), Err('a'));
+        assert_eq!(parse_digits("1 3"), Err(' '));
     }
 }
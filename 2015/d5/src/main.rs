@@ -1,9 +1,11 @@
-use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
-use itertools::Itertools;
+mod rules;
+
+use rules::RuleSet;
 
 fn parse_text<P>(path: P) -> String
 where
@@ -12,70 +14,31 @@ where
     fs::read_to_string(path).unwrap()
 }
 
-fn is_nice_1<S: AsRef<str>>(s: S) -> bool {
-    let chars = s.as_ref().chars().collect::<Vec<char>>();
-    let nvowels = chars
-        .iter()
-        .filter(|&&c| c == 'a' || c == 'e' || c == 'i' || c == 'o' || c == 'u')
-        .count();
-
-    let has_double = chars
-        .windows(2)
-        .any(|pair| if let [x, y] = pair { x == y } else { false });
+const PART1_RULES: &str = "vowels 3; forbid ab cd pq xy; double 0";
+const PART2_RULES: &str = "pair; double 1";
 
-    let all_pairs_valid = chars.windows(2).all(|pair| {
-        pair != ['a', 'b'] && pair != ['c', 'd'] && pair != ['p', 'q'] && pair != ['x', 'y']
-    });
-
-    nvowels >= 3 && has_double && all_pairs_valid
+fn count_nice(rules: &str) {
+    let rules = RuleSet::from_str(rules).unwrap();
+    let res = parse_text("input.txt")
+        .lines()
+        .filter(|s| rules.is_nice(s))
+        .count();
+    println!("{}", res);
 }
 
-fn is_nice_2<S: AsRef<str>>(s: S) -> bool {
-    let chars = s.as_ref().chars().collect::<Vec<char>>();
-
-    let mut pair_positions = HashMap::with_capacity(chars.len());
-    for (i, pair) in chars.windows(2).enumerate() {
-        pair_positions
-            .entry(pair)
-            .or_insert_with(|| Vec::with_capacity(chars.len()))
-            .push(i);
+fn explain(rules: &str, s: &str) {
+    match RuleSet::from_str(rules).unwrap().check(s) {
+        Ok(()) => println!("{} is nice", s),
+        Err(failure) => println!("{} is naughty: {}", s, failure),
     }
-
-    let pairs_ok = pair_positions.values().any(|positions| {
-        positions.len() >= 2
-            && positions
-                .iter()
-                .combinations(2)
-                .filter(|pair| pair[0] + 1 != *pair[1])
-                .count()
-                >= 1
-    });
-
-    let repeat_ok = chars.windows(3).any(|window| {
-        if let [x, _, y] = window {
-            x == y
-        } else {
-            false
-        }
-    });
-
-    repeat_ok && pairs_ok
 }
 
 fn part1() {
-    let res = parse_text("input.txt")
-        .lines()
-        .filter(|s| is_nice_1(s))
-        .count();
-    println!("{}", res);
+    count_nice(PART1_RULES);
 }
 
 fn part2() {
-    let res = parse_text("input.txt")
-        .lines()
-        .filter(|s| is_nice_2(s))
-        .count();
-    println!("{}", res);
+    count_nice(PART2_RULES);
 }
 
 fn main() {
@@ -83,14 +46,25 @@ fn main() {
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "rules" => count_nice(&args[2]),
+        "explain" => explain(&args[2], &args[3]),
+        _ => println!("Must pass either '1', '2', 'rules <rules>' or 'explain <rules> <string>'."),
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
+    fn is_nice_1(s: &str) -> bool {
+        RuleSet::from_str(PART1_RULES).unwrap().is_nice(s)
+    }
+
+    fn is_nice_2(s: &str) -> bool {
+        RuleSet::from_str(PART2_RULES).unwrap().is_nice(s)
+    }
+
     #[test]
     fn test_is_nice_1() {
         assert_eq!(is_nice_1("ugknbfddgicrmopn"), true);
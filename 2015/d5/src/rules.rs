@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// One rule per `;`-separated clause:
+//   vowels N        at least N vowels
+//   forbid S1 S2..  none of the given substrings
+//   double K        some letter repeated with exactly K letters in between
+//   pair            some pair of letters appearing twice without overlapping
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    MinVowels(usize),
+    Forbidden(Vec<Vec<u8>>),
+    Double(usize),
+    RepeatedPair,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RuleParseErr(pub String);
+
+impl FromStr for Rule {
+    type Err = RuleParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || RuleParseErr(s.trim().to_string());
+        let words: Vec<&str> = s.split_ascii_whitespace().collect();
+
+        match words.as_slice() {
+            ["vowels", n] => Ok(Rule::MinVowels(n.parse().map_err(|_| err())?)),
+            ["forbid", subs @ ..] if !subs.is_empty() => Ok(Rule::Forbidden(
+                subs.iter().map(|sub| sub.bytes().collect()).collect(),
+            )),
+            ["double", gap] => Ok(Rule::Double(gap.parse().map_err(|_| err())?)),
+            ["pair"] => Ok(Rule::RepeatedPair),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Failure {
+    NotEnoughVowels { found: usize, required: usize },
+    Forbidden { substring: String, offset: usize },
+    NoDouble(usize),
+    NoRepeatedPair,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::NotEnoughVowels { found, required } => {
+                write!(f, "has {} vowels, needs at least {}", found, required)
+            }
+            Failure::Forbidden { substring, offset } => {
+                write!(f, "contains forbidden '{}' at {}", substring, offset)
+            }
+            Failure::NoDouble(0) => write!(f, "has no letter appearing twice in a row"),
+            Failure::NoDouble(gap) => {
+                write!(f, "has no letter repeated with {} letters in between", gap)
+            }
+            Failure::NoRepeatedPair => write!(f, "has no pair appearing twice"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet(Vec<Rule>);
+
+impl FromStr for RuleSet {
+    type Err = RuleParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(';')
+            .filter(|clause| !clause.trim().is_empty())
+            .map(Rule::from_str)
+            .collect::<Result<_, _>>()
+            .map(RuleSet)
+    }
+}
+
+// Per-rule state while scanning a string.
+enum State {
+    Vowels(usize),
+    Forbidden(Option<Failure>),
+    Double(bool),
+    Pair(HashMap<[u8; 2], usize>, bool),
+}
+
+impl State {
+    fn new(rule: &Rule) -> Self {
+        match rule {
+            Rule::MinVowels(_) => State::Vowels(0),
+            Rule::Forbidden(_) => State::Forbidden(None),
+            Rule::Double(_) => State::Double(false),
+            Rule::RepeatedPair => State::Pair(HashMap::new(), false),
+        }
+    }
+
+    fn feed(&mut self, rule: &Rule, s: &[u8], i: usize) {
+        let c = s[i];
+        match (self, rule) {
+            (State::Vowels(n), _) if b"aeiou".contains(&c) => *n += 1,
+            (State::Forbidden(found @ None), Rule::Forbidden(subs)) => {
+                *found = subs
+                    .iter()
+                    .find(|sub| !sub.is_empty() && s[..=i].ends_with(sub))
+                    .map(|sub| Failure::Forbidden {
+                        substring: String::from_utf8_lossy(sub).into_owned(),
+                        offset: i + 1 - sub.len(),
+                    });
+            }
+            (State::Double(found @ false), Rule::Double(gap)) => {
+                *found = i > *gap && s[i - gap - 1] == c;
+            }
+            (State::Pair(first_seen, found @ false), _) if i > 0 => {
+                let start = *first_seen.entry([s[i - 1], c]).or_insert(i - 1);
+                *found = start + 1 < i - 1;
+            }
+            _ => (),
+        }
+    }
+
+    fn finish(self, rule: &Rule) -> Result<(), Failure> {
+        match (self, rule) {
+            (State::Vowels(found), &Rule::MinVowels(required)) if found < required => {
+                Err(Failure::NotEnoughVowels { found, required })
+            }
+            (State::Forbidden(Some(failure)), _) => Err(failure),
+            (State::Double(false), &Rule::Double(gap)) => Err(Failure::NoDouble(gap)),
+            (State::Pair(_, false), _) => Err(Failure::NoRepeatedPair),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl RuleSet {
+    // Scans the string once, feeding every byte to all rules, and reports the
+    // first rule (in declaration order) that failed.
+    pub fn check(&self, s: &str) -> Result<(), Failure> {
+        let bytes = s.as_bytes();
+        let mut states: Vec<State> = self.0.iter().map(State::new).collect();
+
+        for i in 0..bytes.len() {
+            for (state, rule) in states.iter_mut().zip(&self.0) {
+                state.feed(rule, bytes, i);
+            }
+        }
+
+        states
+            .into_iter()
+            .zip(&self.0)
+            .try_for_each(|(state, rule)| state.finish(rule))
+    }
+
+    pub fn is_nice(&self, s: &str) -> bool {
+        self.check(s).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            RuleSet::from_str("vowels 3; forbid ab cd; double 0; pair;"),
+            Ok(RuleSet(vec![
+                Rule::MinVowels(3),
+                Rule::Forbidden(vec![b"ab".to_vec(), b"cd".to_vec()]),
+                Rule::Double(0),
+                Rule::RepeatedPair,
+            ]))
+        );
+        assert_eq!(
+            RuleSet::from_str("vowels three"),
+            Err(RuleParseErr("vowels three".to_string()))
+        );
+        assert_eq!(
+            RuleSet::from_str("forbid"),
+            Err(RuleParseErr("forbid".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let rules = RuleSet::from_str("vowels 3; forbid ab cd pq xy; double 0").unwrap();
+        assert_eq!(rules.check("jchzalrnumimnmhp"), Err(Failure::NoDouble(0)));
+        assert_eq!(
+            rules.check("haegwjzuvuyypxyu"),
+            Err(Failure::Forbidden {
+                substring: "xy".to_string(),
+                offset: 13
+            })
+        );
+        assert_eq!(
+            rules.check("dvszwmarrgswjxmb"),
+            Err(Failure::NotEnoughVowels {
+                found: 1,
+                required: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_pair() {
+        let rules = RuleSet::from_str("pair").unwrap();
+        assert!(rules.is_nice("aabcdefgaa"));
+        assert!(rules.is_nice("xyxy"));
+        assert!(rules.is_nice("aaaa"));
+        assert!(!rules.is_nice("aaa"));
+        assert!(!rules.is_nice("a"));
+    }
+
+    #[test]
+    fn test_double_gap() {
+        let rules = RuleSet::from_str("double 2").unwrap();
+        assert!(rules.is_nice("abca"));
+        assert!(!rules.is_nice("aba"));
+        assert!(!rules.is_nice("ab"));
+    }
+}
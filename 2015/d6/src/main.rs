@@ -1,8 +1,13 @@
 use std::env;
-use std::fs;
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::path::Path;
 use std::str::FromStr;
 
+mod sparse;
+
+use sparse::SparseGrid;
+
 #[derive(Debug, PartialEq, Eq)]
 enum Op {
     TurnOn,
@@ -159,26 +164,47 @@ where
         .collect()
 }
 
-fn part1() {
+fn part1(sparse: bool) {
     let instructions = parse_instructions("input.txt");
-    let res = Grid::from_instructions(instructions, &Bulb::exec_1).count_lit();
+    let res = if sparse {
+        SparseGrid::from_instructions(&instructions, &Bulb::exec_1).count_lit()
+    } else {
+        Grid::from_instructions(instructions, &Bulb::exec_1).count_lit()
+    };
 
     println!("{}", res);
 }
 
-fn part2() {
+fn part2(sparse: bool) {
     let instructions = parse_instructions("input.txt");
-    let res = Grid::from_instructions(instructions, &Bulb::exec_2).total_brightness();
+    let res = if sparse {
+        SparseGrid::from_instructions(&instructions, &Bulb::exec_2).total_brightness()
+    } else {
+        Grid::from_instructions(instructions, &Bulb::exec_2).total_brightness()
+    };
 
     println!("{}", res);
 }
 
+fn render(part: &str, path: &str) {
+    let instructions = parse_instructions("input.txt");
+    let grid = match part {
+        "1" => SparseGrid::from_instructions(&instructions, &Bulb::exec_1),
+        _ => SparseGrid::from_instructions(&instructions, &Bulb::exec_2),
+    };
+
+    let mut out = BufWriter::new(File::create(path).unwrap());
+    grid.write_pgm(&mut out).unwrap();
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let sparse = args.get(2).is_some_and(|arg| arg == "sparse");
     match &args[1][..] {
-        "1" => part1(),
-        "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "1" => part1(sparse),
+        "2" => part2(sparse),
+        "render" => render(&args[2], &args[3]),
+        _ => println!("Must pass either '1' or '2' (optionally followed by 'sparse'), or 'render <part> <file.pgm>'."),
     }
 }
 
@@ -0,0 +1,176 @@
+use std::io::{self, Write};
+
+use crate::{Bulb, Instruction, Op};
+
+// Splits the plane along every rectangle edge, so that every instruction
+// covers whole compressed cells and each cell stands for a block of bulbs
+// that always share the same state.
+pub struct SparseGrid {
+    cols: Vec<usize>,
+    rows: Vec<usize>,
+    cells: Vec<Bulb>,
+}
+
+fn boundaries<I>(edges: I, len: usize) -> Vec<usize>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut out: Vec<usize> = edges.chain([0, len]).collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+fn compressed(boundaries: &[usize], from: usize, to: usize) -> std::ops::Range<usize> {
+    let start = boundaries.binary_search(&from).unwrap();
+    let end = boundaries.binary_search(&(to + 1)).unwrap();
+    start..end
+}
+
+impl SparseGrid {
+    pub fn from_instructions<F>(instructions: &[Instruction], executor: &F) -> Self
+    where
+        F: Fn(&Bulb, &Op) -> Bulb,
+    {
+        let width = instructions
+            .iter()
+            .map(|i| i.rect.1 .0 + 1)
+            .max()
+            .unwrap_or(0);
+        let height = instructions
+            .iter()
+            .map(|i| i.rect.1 .1 + 1)
+            .max()
+            .unwrap_or(0);
+
+        let cols = boundaries(
+            instructions
+                .iter()
+                .flat_map(|i| [i.rect.0 .0, i.rect.1 .0 + 1]),
+            width,
+        );
+        let rows = boundaries(
+            instructions
+                .iter()
+                .flat_map(|i| [i.rect.0 .1, i.rect.1 .1 + 1]),
+            height,
+        );
+
+        let ncols = cols.len() - 1;
+        let mut cells = vec![Bulb(0); ncols * (rows.len() - 1)];
+        for Instruction { op, rect } in instructions {
+            let ((left, top), (right, bot)) = *rect;
+            for row in compressed(&rows, top, bot) {
+                for col in compressed(&cols, left, right) {
+                    let i = row * ncols + col;
+                    cells[i] = executor(&cells[i], op);
+                }
+            }
+        }
+
+        Self { cols, rows, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cols[self.cols.len() - 1]
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows[self.rows.len() - 1]
+    }
+
+    fn cells_with_area(&self) -> impl Iterator<Item = (&Bulb, usize)> {
+        let ncols = self.cols.len() - 1;
+        self.cells.iter().enumerate().map(move |(i, bulb)| {
+            let (row, col) = (i / ncols, i % ncols);
+            let area =
+                (self.cols[col + 1] - self.cols[col]) * (self.rows[row + 1] - self.rows[row]);
+            (bulb, area)
+        })
+    }
+
+    pub fn count_lit(&self) -> usize {
+        self.cells_with_area()
+            .filter(|(bulb, _)| bulb.is_lit())
+            .map(|(_, area)| area)
+            .sum()
+    }
+
+    pub fn total_brightness(&self) -> usize {
+        self.cells_with_area()
+            .map(|(bulb, area)| bulb.0 * area)
+            .sum()
+    }
+
+    // Binary greymap, with the brightest bulb in white.
+    pub fn write_pgm<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let max = self.cells.iter().map(|b| b.0).max().unwrap_or(0).max(1);
+        let ncols = self.cols.len() - 1;
+
+        write!(out, "P5\n{} {}\n255\n", self.width(), self.height())?;
+        let mut line = Vec::with_capacity(self.width());
+        for (row, bounds) in self.rows.windows(2).enumerate() {
+            line.clear();
+            for (col, col_bounds) in self.cols.windows(2).enumerate() {
+                let shade = (self.cells[row * ncols + col].0 * 255 / max) as u8;
+                line.extend(std::iter::repeat_n(shade, col_bounds[1] - col_bounds[0]));
+            }
+            for _ in bounds[0]..bounds[1] {
+                out.write_all(&line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid;
+    use std::str::FromStr;
+
+    fn instructions(raw: &[&str]) -> Vec<Instruction> {
+        raw.iter()
+            .map(|s| Instruction::from_str(s).unwrap())
+            .collect()
+    }
+
+    const EXAMPLE: [&str; 4] = [
+        "turn on 0,0 through 999,999",
+        "toggle 0,0 through 999,0",
+        "turn off 499,499 through 500,500",
+        "toggle 10,20 through 600,700",
+    ];
+
+    #[test]
+    fn test_matches_dense_grid() {
+        for executor in [Bulb::exec_1, Bulb::exec_2] {
+            let sparse = SparseGrid::from_instructions(&instructions(&EXAMPLE), &executor);
+            let dense = Grid::from_instructions(instructions(&EXAMPLE), &executor);
+            assert_eq!(sparse.count_lit(), dense.count_lit());
+            assert_eq!(sparse.total_brightness(), dense.total_brightness());
+        }
+    }
+
+    #[test]
+    fn test_dimensions_from_instructions() {
+        let grid = SparseGrid::from_instructions(
+            &instructions(&["turn on 2,3 through 4,5", "toggle 3,3 through 3,9"]),
+            &Bulb::exec_1,
+        );
+        assert_eq!((grid.width(), grid.height()), (5, 10));
+        assert_eq!(grid.count_lit(), 9 - 3 + 4);
+    }
+
+    #[test]
+    fn test_write_pgm() {
+        let grid = SparseGrid::from_instructions(
+            &instructions(&["turn on 0,0 through 1,0", "toggle 1,0 through 2,1"]),
+            &Bulb::exec_2,
+        );
+        let mut out = Vec::new();
+        grid.write_pgm(&mut out).unwrap();
+        assert_eq!(out, b"P5\n3 2\n255\n\x55\xff\xaa\x00\xaa\xaa".to_vec());
+    }
+}
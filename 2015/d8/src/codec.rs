@@ -0,0 +1,338 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EscapeSet {
+    pub hex: bool,
+    pub newline_tab: bool,
+    pub unicode: bool,
+}
+
+impl EscapeSet {
+    // \" and \\ are always allowed
+    pub const MINIMAL: Self = Self {
+        hex: false,
+        newline_tab: false,
+        unicode: false,
+    };
+    pub const AOC: Self = Self {
+        hex: true,
+        ..Self::MINIMAL
+    };
+    pub const FULL: Self = Self {
+        hex: true,
+        newline_tab: true,
+        unicode: true,
+    };
+}
+
+// All offsets are byte offsets into the literal, quotes included.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    MissingOpeningQuote,
+    MissingClosingQuote,
+    UnescapedQuote(usize),
+    UnknownEscape(usize, u8),
+    IncompleteEscape(usize),
+    InvalidHex(usize),
+    InvalidCodePoint(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingOpeningQuote => write!(f, "literal does not start with a quote"),
+            Self::MissingClosingQuote => write!(f, "literal does not end with a quote"),
+            Self::UnescapedQuote(i) => write!(f, "unescaped quote at byte {}", i),
+            Self::UnknownEscape(i, c) => {
+                write!(f, "unknown escape '\\{}' at byte {}", c.escape_ascii(), i)
+            }
+            Self::IncompleteEscape(i) => write!(f, "incomplete escape at byte {}", i),
+            Self::InvalidHex(i) => write!(f, "invalid hex digits in escape at byte {}", i),
+            Self::InvalidCodePoint(i) => write!(f, "invalid unicode escape at byte {}", i),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Lengths {
+    pub code: usize,
+    pub memory: usize,
+    pub encoded: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Codec {
+    pub escapes: EscapeSet,
+    // write bytes outside of printable ASCII as escapes instead of verbatim
+    pub escape_non_printable: bool,
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Self {
+            escapes: EscapeSet::AOC,
+            escape_non_printable: false,
+        }
+    }
+}
+
+fn parse_hex(digits: &[u8]) -> Option<u32> {
+    let s = std::str::from_utf8(digits).ok()?;
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(s, 16).ok()
+}
+
+impl Codec {
+    pub fn new(escapes: EscapeSet) -> Self {
+        Self {
+            escapes,
+            ..Self::default()
+        }
+    }
+
+    pub fn escaping_non_printable(self) -> Self {
+        Self {
+            escape_non_printable: true,
+            ..self
+        }
+    }
+
+    pub fn decode(&self, literal: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        if literal.first() != Some(&b'"') {
+            return Err(DecodeError::MissingOpeningQuote);
+        }
+        let end = literal.len() - 1;
+        if end == 0 {
+            return Err(DecodeError::MissingClosingQuote);
+        }
+
+        let mut out = Vec::with_capacity(literal.len());
+        let mut i = 1;
+        while i < end {
+            match literal[i] {
+                b'"' => return Err(DecodeError::UnescapedQuote(i)),
+                b'\\' => i = self.decode_escape(literal, i, end, &mut out)?,
+                b => {
+                    out.push(b);
+                    i += 1;
+                }
+            }
+        }
+
+        match literal[end] {
+            b'"' if i == end => Ok(out),
+            _ => Err(DecodeError::MissingClosingQuote),
+        }
+    }
+
+    // Decodes the escape starting at the backslash in `start`, and returns
+    // the offset right after it.
+    fn decode_escape(
+        &self,
+        literal: &[u8],
+        start: usize,
+        end: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<usize, DecodeError> {
+        let kind = match literal.get(start + 1) {
+            Some(&kind) if start + 1 < end => kind,
+            _ => return Err(DecodeError::IncompleteEscape(start)),
+        };
+
+        match kind {
+            b'"' | b'\\' => out.push(kind),
+            b'n' if self.escapes.newline_tab => out.push(b'\n'),
+            b't' if self.escapes.newline_tab => out.push(b'\t'),
+            b'x' if self.escapes.hex => {
+                if start + 4 > end {
+                    return Err(DecodeError::IncompleteEscape(start));
+                }
+                let value = parse_hex(&literal[start + 2..start + 4])
+                    .ok_or(DecodeError::InvalidHex(start))?;
+                out.push(value as u8);
+                return Ok(start + 4);
+            }
+            b'u' if self.escapes.unicode => {
+                if start + 3 > end {
+                    return Err(DecodeError::IncompleteEscape(start));
+                }
+                if literal[start + 2] != b'{' {
+                    return Err(DecodeError::InvalidCodePoint(start));
+                }
+                let close = literal[start + 3..end]
+                    .iter()
+                    .position(|&b| b == b'}')
+                    .map(|p| start + 3 + p)
+                    .ok_or(DecodeError::IncompleteEscape(start))?;
+                let digits = &literal[start + 3..close];
+                if digits.len() > 6 {
+                    return Err(DecodeError::InvalidCodePoint(start));
+                }
+                let c = parse_hex(digits)
+                    .and_then(char::from_u32)
+                    .ok_or(DecodeError::InvalidCodePoint(start))?;
+                out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                return Ok(close + 1);
+            }
+            _ => return Err(DecodeError::UnknownEscape(start, kind)),
+        }
+
+        Ok(start + 2)
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bytes.len() * 2 + 2);
+
+        out.push(b'"');
+        for &b in bytes {
+            match b {
+                b'"' => out.extend_from_slice(b"\\\""),
+                b'\\' => out.extend_from_slice(b"\\\\"),
+                b'\n' if self.escape_non_printable && self.escapes.newline_tab => {
+                    out.extend_from_slice(b"\\n")
+                }
+                b'\t' if self.escape_non_printable && self.escapes.newline_tab => {
+                    out.extend_from_slice(b"\\t")
+                }
+                b if self.escape_non_printable
+                    && self.escapes.hex
+                    && !(0x20..0x7f).contains(&b) =>
+                {
+                    out.extend_from_slice(format!("\\x{:02x}", b).as_bytes())
+                }
+                b => out.push(b),
+            }
+        }
+        out.push(b'"');
+
+        out
+    }
+
+    pub fn lengths(&self, literal: &str) -> Result<Lengths, DecodeError> {
+        let literal = literal.as_bytes();
+        Ok(Lengths {
+            code: literal.len(),
+            memory: self.decode(literal)?.len(),
+            encoded: self.encode(literal).len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+        Codec::default().decode(s.as_bytes())
+    }
+
+    fn encode(s: &str) -> String {
+        String::from_utf8(Codec::default().encode(s.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("\"\""), Ok(b"".to_vec()));
+        assert_eq!(decode("\"abc\""), Ok(b"abc".to_vec()));
+        assert_eq!(decode("\"aaa\\\"aaa\""), Ok(b"aaa\"aaa".to_vec()));
+        assert_eq!(decode("\"\\x27\""), Ok(b"\x27".to_vec()));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode("abc\""), Err(DecodeError::MissingOpeningQuote));
+        assert_eq!(decode("\""), Err(DecodeError::MissingClosingQuote));
+        assert_eq!(decode("\"abc"), Err(DecodeError::MissingClosingQuote));
+        assert_eq!(decode("\"a\"c\""), Err(DecodeError::UnescapedQuote(2)));
+        assert_eq!(
+            decode("\"ab\\n\""),
+            Err(DecodeError::UnknownEscape(3, b'n'))
+        );
+        assert_eq!(decode("\"ab\\\""), Err(DecodeError::IncompleteEscape(3)));
+        assert_eq!(decode("\"\\x2\""), Err(DecodeError::IncompleteEscape(1)));
+        assert_eq!(decode("\"\\x2g\""), Err(DecodeError::InvalidHex(1)));
+    }
+
+    #[test]
+    fn test_decode_full_escapes() {
+        let codec = Codec::new(EscapeSet::FULL);
+        assert_eq!(codec.decode(b"\"a\\tb\\n\""), Ok(b"a\tb\n".to_vec()));
+        assert_eq!(
+            codec.decode(b"\"\\u{e9}!\""),
+            Ok("\u{e9}!".as_bytes().to_vec())
+        );
+        assert_eq!(
+            codec.decode(b"\"\\u{d800}\""),
+            Err(DecodeError::InvalidCodePoint(1))
+        );
+        assert_eq!(
+            codec.decode(b"\"\\u{41\""),
+            Err(DecodeError::IncompleteEscape(1))
+        );
+        // no closing quote, the brace is the last byte
+        assert_eq!(
+            codec.decode(b"\"\\u{"),
+            Err(DecodeError::IncompleteEscape(1))
+        );
+        assert_eq!(
+            codec.decode(b"\"\\u{1"),
+            Err(DecodeError::IncompleteEscape(1))
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("\"\""), "\"\\\"\\\"\"");
+
+        assert_eq!(encode("\"abc\""), "\"\\\"abc\\\"\"");
+        assert_eq!(
+            encode("\"\\x27\""),
+            "\" \\\" \\\\x27 \\\" \"".replace(" ", "")
+        );
+    }
+
+    #[test]
+    fn test_encode_non_printable() {
+        let codec = Codec::new(EscapeSet::FULL).escaping_non_printable();
+        assert_eq!(
+            codec.encode(b"a\tb\n\x00\xff"),
+            b"\"a\\tb\\n\\x00\\xff\"".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for escapes in [EscapeSet::MINIMAL, EscapeSet::AOC, EscapeSet::FULL] {
+            for codec in [
+                Codec::new(escapes),
+                Codec::new(escapes).escaping_non_printable(),
+            ] {
+                assert_eq!(codec.decode(&codec.encode(&bytes)), Ok(bytes.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lengths() {
+        let codec = Codec::default();
+        assert_eq!(
+            codec.lengths("\"aaa\\\"aaa\""),
+            Ok(Lengths {
+                code: 10,
+                memory: 7,
+                encoded: 16
+            })
+        );
+        assert_eq!(
+            codec.lengths("\"\\x27\""),
+            Ok(Lengths {
+                code: 6,
+                memory: 1,
+                encoded: 11
+            })
+        );
+    }
+}
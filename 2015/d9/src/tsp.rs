@@ -0,0 +1,221 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Dist, Node};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    Shortest,
+    Longest,
+}
+
+impl Objective {
+    fn is_better(&self, candidate: Dist, current: Dist) -> bool {
+        match self {
+            Objective::Shortest => candidate < current,
+            Objective::Longest => candidate > current,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TspError {
+    NoCities,
+    TooManyCities(usize),
+    // no route visits every city, e.g. because the graph is disconnected
+    NoRoute,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Route<'a> {
+    pub cities: Vec<Node<'a>>,
+    pub distance: Dist,
+}
+
+pub struct Tsp<'a> {
+    cities: Vec<Node<'a>>,
+    distances: Vec<Vec<Option<Dist>>>,
+}
+
+// The dp tables have 2^n * n entries, 24 bytes for both tables together, so
+// 16 cities already take 24 MB.
+const MAX_CITIES: usize = 16;
+
+impl<'a> Tsp<'a> {
+    pub fn new(distances: &HashMap<(Node<'a>, Node<'a>), Dist>) -> Self {
+        let cities: Vec<Node> = distances
+            .keys()
+            .flat_map(|&(from, to)| [from, to])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index: HashMap<Node, usize> = cities.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+
+        let mut matrix = vec![vec![None; cities.len()]; cities.len()];
+        for (&(from, to), &dist) in distances {
+            matrix[index[from]][index[to]] = Some(dist);
+            matrix[index[to]][index[from]] = Some(dist);
+        }
+
+        Tsp {
+            cities,
+            distances: matrix,
+        }
+    }
+
+    // Held-Karp: best[mask * n + last] is the best distance of a path that
+    // visits exactly the cities in mask and ends at last.
+    pub fn solve(&self, objective: Objective, round_trip: bool) -> Result<Route<'a>, TspError> {
+        let n = self.cities.len();
+        if n == 0 {
+            return Err(TspError::NoCities);
+        }
+        if n > MAX_CITIES {
+            return Err(TspError::TooManyCities(n));
+        }
+
+        let full = (1 << n) - 1;
+        let mut best: Vec<Option<Dist>> = vec![None; n << n];
+        let mut parent = vec![usize::MAX; n << n];
+
+        // a cycle can start anywhere, so pin it to the first city
+        let starts = if round_trip { 1 } else { n };
+        for start in 0..starts {
+            best[(1 << start) * n + start] = Some(0);
+        }
+
+        for mask in 1..=full {
+            for last in 0..n {
+                let dist = match best[mask * n + last] {
+                    Some(dist) => dist,
+                    None => continue,
+                };
+                for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                    if let Some(step) = self.distances[last][next] {
+                        let at = (mask | (1 << next)) * n + next;
+                        let candidate = dist + step;
+                        if best[at].is_none_or(|current| objective.is_better(candidate, current)) {
+                            best[at] = Some(candidate);
+                            parent[at] = last;
+                        }
+                    }
+                }
+            }
+        }
+
+        // a single city is a round trip on its own
+        let closing = |last: usize| {
+            if round_trip && n > 1 {
+                self.distances[last][0]
+            } else {
+                Some(0)
+            }
+        };
+        let (last, distance) = (0..n)
+            .filter_map(|last| Some((last, best[full * n + last]? + closing(last)?)))
+            .fold(None, |acc: Option<(usize, Dist)>, (last, dist)| match acc {
+                Some((_, best_dist)) if !objective.is_better(dist, best_dist) => acc,
+                _ => Some((last, dist)),
+            })
+            .ok_or(TspError::NoRoute)?;
+
+        let mut route = Vec::with_capacity(n + 1);
+        let (mut mask, mut city) = (full, last);
+        while city != usize::MAX {
+            route.push(self.cities[city]);
+            let prev = parent[mask * n + city];
+            mask &= !(1 << city);
+            city = prev;
+        }
+        route.reverse();
+        if round_trip {
+            route.push(route[0]);
+        }
+
+        Ok(Route {
+            cities: route,
+            distance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distances;
+
+    const EXAMPLE: &str =
+        "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141";
+
+    #[test]
+    fn test_shortest_path() {
+        let distances = distances(EXAMPLE);
+        let route = Tsp::new(&distances)
+            .solve(Objective::Shortest, false)
+            .unwrap();
+        assert_eq!(route.distance, 605);
+        assert!(
+            route.cities == vec!["London", "Dublin", "Belfast"]
+                || route.cities == vec!["Belfast", "Dublin", "London"]
+        );
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let distances = distances(EXAMPLE);
+        let route = Tsp::new(&distances)
+            .solve(Objective::Longest, false)
+            .unwrap();
+        assert_eq!(route.distance, 982);
+        assert_eq!(route.cities[1], "London");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let distances = distances(EXAMPLE);
+        let route = Tsp::new(&distances)
+            .solve(Objective::Shortest, true)
+            .unwrap();
+        assert_eq!(route.distance, 464 + 518 + 141);
+        assert_eq!(route.cities.len(), 4);
+        assert_eq!(route.cities[0], route.cities[3]);
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let disconnected = distances("A to B = 1\nC to D = 2");
+        let tsp = Tsp::new(&disconnected);
+        assert_eq!(
+            tsp.solve(Objective::Shortest, false),
+            Err(TspError::NoRoute)
+        );
+
+        // a path exists, but there is no edge to close the cycle
+        let line = distances("A to B = 1\nB to C = 2");
+        let tsp = Tsp::new(&line);
+        assert_eq!(tsp.solve(Objective::Longest, false).unwrap().distance, 3);
+        assert_eq!(tsp.solve(Objective::Longest, true), Err(TspError::NoRoute));
+    }
+
+    #[test]
+    fn test_single_city() {
+        let distances = HashMap::from([(("A", "A"), 5)]);
+        let tsp = Tsp::new(&distances);
+        let route = tsp.solve(Objective::Shortest, true).unwrap();
+        assert_eq!(route.cities, vec!["A", "A"]);
+        assert_eq!(route.distance, 0);
+        assert_eq!(tsp.solve(Objective::Longest, false).unwrap().distance, 0);
+    }
+
+    #[test]
+    fn test_too_many_cities() {
+        let names: Vec<String> = (0..17).map(|i| format!("C{}", i)).collect();
+        let distances = names[1..]
+            .iter()
+            .map(|name| ((names[0].as_str(), name.as_str()), 1))
+            .collect();
+        assert_eq!(
+            Tsp::new(&distances).solve(Objective::Shortest, false),
+            Err(TspError::TooManyCities(17))
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DriftErr {
+    NoChanges,
+    // every pass moves all frequencies by `drift`, and none of them ever
+    // lands on another one
+    NoRepeat { drift: i64 },
+}
+
+impl fmt::Display for DriftErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriftErr::NoChanges => write!(f, "no frequency changes"),
+            DriftErr::NoRepeat { drift } => write!(
+                f,
+                "no frequency is ever reached twice (drift of {} per pass)",
+                drift
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Repeat {
+    pub frequency: i64,
+    // number of changes applied when the frequency is reached again
+    pub step: u64,
+}
+
+pub struct Analyzer {
+    // frequency before each change of the first pass
+    prefix: Vec<i64>,
+    drift: i64,
+}
+
+impl Analyzer {
+    pub fn new(changes: &[i64]) -> Analyzer {
+        let mut prefix = Vec::with_capacity(changes.len());
+        let mut frequency = 0;
+        for change in changes {
+            prefix.push(frequency);
+            frequency += change;
+        }
+        Analyzer {
+            prefix,
+            drift: frequency,
+        }
+    }
+
+    // Frequency after a full pass over the changes.
+    pub fn drift(&self) -> i64 {
+        self.drift
+    }
+
+    // The frequency before change i of pass k is prefix[i] + k * drift, so
+    // prefix[j] comes back to an earlier prefix[i] after (prefix[i] -
+    // prefix[j]) / drift passes, if the two are congruent modulo the drift.
+    // The closest such prefix[i] in the direction of the drift is the first
+    // one reached from prefix[j].
+    pub fn first_repeat(&self) -> Result<Repeat, DriftErr> {
+        let n = self.prefix.len();
+        if n == 0 {
+            return Err(DriftErr::NoChanges);
+        }
+
+        let mut seen = HashSet::with_capacity(n);
+        for (step, &frequency) in self.prefix.iter().enumerate() {
+            if !seen.insert(frequency) {
+                return Ok(Repeat {
+                    frequency,
+                    step: step as u64,
+                });
+            }
+        }
+        if self.drift == 0 {
+            return Ok(Repeat {
+                frequency: 0,
+                step: n as u64,
+            });
+        }
+
+        // looking at it so that the drift is positive
+        let sign = self.drift.signum();
+        let drift = self.drift.abs();
+        let mut classes: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+        for (i, &frequency) in self.prefix.iter().enumerate() {
+            let frequency = frequency * sign;
+            classes
+                .entry(frequency.rem_euclid(drift))
+                .or_default()
+                .push((frequency, i));
+        }
+
+        classes
+            .values_mut()
+            .flat_map(|class| {
+                class.sort_unstable();
+                class
+                    .windows(2)
+                    .map(|pair| {
+                        let ((from, j), (to, _)) = (pair[0], pair[1]);
+                        let passes = ((to - from) / drift) as u64;
+                        Repeat {
+                            frequency: to * sign,
+                            step: passes * n as u64 + j as u64,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .min_by_key(|repeat| repeat.step)
+            .ok_or(DriftErr::NoRepeat { drift: self.drift })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_repeat(changes: &[i64]) -> Result<i64, DriftErr> {
+        Analyzer::new(changes).first_repeat().map(|r| r.frequency)
+    }
+
+    // what part 2 used to do, with a bound on the number of passes
+    fn brute_force(changes: &[i64], max_passes: usize) -> Option<Repeat> {
+        let mut seen = HashSet::new();
+        let mut frequency = 0;
+        for step in 0..changes.len() * max_passes {
+            if !seen.insert(frequency) {
+                return Some(Repeat {
+                    frequency,
+                    step: step as u64,
+                });
+            }
+            frequency += changes[step % changes.len()];
+        }
+        None
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(first_repeat(&[1, -1]), Ok(0));
+        assert_eq!(first_repeat(&[3, 3, 4, -2, -4]), Ok(10));
+        assert_eq!(first_repeat(&[-6, 3, 8, 5, -6]), Ok(5));
+        assert_eq!(first_repeat(&[7, 7, -2, -7, -4]), Ok(14));
+    }
+
+    #[test]
+    fn test_no_repeat() {
+        assert_eq!(first_repeat(&[1, 1]), Err(DriftErr::NoRepeat { drift: 2 }));
+        assert_eq!(
+            first_repeat(&[-3, 1]),
+            Err(DriftErr::NoRepeat { drift: -2 })
+        );
+        assert_eq!(first_repeat(&[]), Err(DriftErr::NoChanges));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut seed = 7u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) % 21) as i64 - 10
+        };
+        for len in 1..12 {
+            for _ in 0..50 {
+                let changes: Vec<i64> = (0..len).map(|_| next()).collect();
+                let analyzer = Analyzer::new(&changes);
+                match analyzer.first_repeat() {
+                    Ok(repeat) => assert_eq!(Some(repeat), brute_force(&changes, 300)),
+                    Err(_) => assert_eq!(brute_force(&changes, 300), None),
+                }
+            }
+        }
+    }
+}
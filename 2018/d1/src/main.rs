@@ -1,36 +1,28 @@
-use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::str::FromStr;
 
-fn part1() {
+mod drift;
+
+use drift::Analyzer;
+
+fn read_changes() -> Vec<i64> {
     let content = fs::read_to_string("input.txt").unwrap();
-    let result: i32 = content
+    content
         .lines()
-        .map(|line| i32::from_str(line).unwrap())
-        .sum();
-    println!("{}", result);
+        .map(|line| i64::from_str(line).unwrap())
+        .collect()
 }
 
-fn part2() {
-    let content = fs::read_to_string("input.txt").unwrap();
-    let values: Vec<i32> = content
-        .lines()
-        .map(|line| i32::from_str(line).unwrap())
-        .collect();
+fn part1() {
+    println!("{}", Analyzer::new(&read_changes()).drift());
+}
 
-    let mut results: HashSet<i32> = HashSet::new();
-    let mut result: i32 = 0;
-    'outer: loop {
-        for value in values.iter() {
-            results.insert(result);
-            result += value;
-            if results.contains(&result) {
-                break 'outer;
-            }
-        }
+fn part2() {
+    match Analyzer::new(&read_changes()).first_repeat() {
+        Ok(repeat) => println!("{}", repeat.frequency),
+        Err(e) => println!("{}", e),
     }
-    println!("{}", result);
 }
 
 fn main() {
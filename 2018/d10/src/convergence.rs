@@ -0,0 +1,197 @@
+use crate::Star;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // least-squares estimate, refined by walking downhill
+    Estimate,
+    // doubling, then binary search for the first second after which the
+    // area grows
+    BinarySearch,
+}
+
+pub fn bounding_box_area(stars: &[Star], t: i64) -> i64 {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (i64::MAX, i64::MAX, i64::MIN, i64::MIN);
+    for star in stars {
+        let (x, y) = star.position_at(t);
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    (max_x - min_x + 1) * (max_y - min_y + 1)
+}
+
+// Time at which the stars are closest together in the least-squares sense,
+// i.e. the minimum of sum(|p_i + v_i * t - centroid(t)|^2).
+pub fn estimate(stars: &[Star]) -> i64 {
+    let n = stars.len() as f64;
+    let mean = |f: fn(&Star) -> i64| stars.iter().map(|s| f(s) as f64).sum::<f64>() / n;
+    let (mx, my, mvx, mvy) = (mean(|s| s.x), mean(|s| s.y), mean(|s| s.vx), mean(|s| s.vy));
+
+    let (mut cov, mut var) = (0.0, 0.0);
+    for s in stars {
+        let (dx, dy) = (s.x as f64 - mx, s.y as f64 - my);
+        let (dvx, dvy) = (s.vx as f64 - mvx, s.vy as f64 - mvy);
+        cov += dx * dvx + dy * dvy;
+        var += dvx * dvx + dvy * dvy;
+    }
+
+    if var == 0.0 {
+        0
+    } else {
+        (-cov / var).round().max(0.0) as i64
+    }
+}
+
+fn local_search(stars: &[Star], mut t: i64) -> i64 {
+    let mut area = bounding_box_area(stars, t);
+    for step in [1, -1] {
+        while t + step >= 0 {
+            let next = bounding_box_area(stars, t + step);
+            if next >= area {
+                break;
+            }
+            t += step;
+            area = next;
+        }
+    }
+    t
+}
+
+// Whether the area grows after second `t`. Over a plateau, it goes the way
+// it goes once the plateau ends, so that a flat second on the way down does
+// not look like the minimum.
+fn rising(stars: &[Star], t: i64) -> bool {
+    let area = bounding_box_area(stars, t);
+    let mut next = t + 1;
+    loop {
+        let next_area = bounding_box_area(stars, next);
+        if next_area != area {
+            return next_area > area;
+        }
+        next += 1;
+    }
+}
+
+fn binary_search(stars: &[Star]) -> i64 {
+    // every second looks the same, and the plateau never ends
+    if stars
+        .iter()
+        .all(|s| (s.vx, s.vy) == (stars[0].vx, stars[0].vy))
+    {
+        return 0;
+    }
+
+    // the area shrinks until the message shows up, and grows forever after
+    let mut hi = 1;
+    while !rising(stars, hi) {
+        hi *= 2;
+    }
+    let mut lo = 0;
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if rising(stars, mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
+
+// Second at which the stars' bounding box is the smallest.
+pub fn converge(stars: &[Star], strategy: Strategy) -> i64 {
+    if stars.is_empty() {
+        return 0;
+    }
+    match strategy {
+        Strategy::Estimate => local_search(stars, estimate(stars)),
+        Strategy::BinarySearch => binary_search(stars),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // draws `rows` at time `t` with the given velocities
+    fn rewind(rows: &[&str], t: i64, velocity: impl Fn(usize) -> (i64, i64)) -> Vec<Star> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| (x as i64, y as i64))
+            })
+            .enumerate()
+            .map(|(i, (x, y))| {
+                let (vx, vy) = velocity(i);
+                Star {
+                    x: x - vx * t,
+                    y: y - vy * t,
+                    vx,
+                    vy,
+                }
+            })
+            .collect()
+    }
+
+    const LH: [&str; 10] = [
+        "#.......#....#",
+        "#.......#....#",
+        "#.......#....#",
+        "#.......#....#",
+        "#.......######",
+        "#.......#....#",
+        "#.......#....#",
+        "#.......#....#",
+        "#.......#....#",
+        "######..#....#",
+    ];
+
+    #[test]
+    fn test_converge() {
+        let velocity = |i: usize| (i as i64 % 7 - 3, (i as i64 * 5) % 9 - 4);
+        let stars = rewind(&LH, 10_345, velocity);
+
+        assert!((estimate(&stars) - 10_345).abs() <= 2);
+        assert_eq!(converge(&stars, Strategy::Estimate), 10_345);
+        assert_eq!(converge(&stars, Strategy::BinarySearch), 10_345);
+    }
+
+    #[test]
+    fn test_converge_skewed() {
+        // everything drifts in the same direction, and only a few stars move
+        // towards the others
+        let velocity = |i: usize| {
+            if i.is_multiple_of(5) {
+                (50, 20)
+            } else {
+                (49, 21)
+            }
+        };
+        let stars = rewind(&LH, 321, velocity);
+
+        assert_eq!(converge(&stars, Strategy::Estimate), 321);
+        assert_eq!(converge(&stars, Strategy::BinarySearch), 321);
+        let points = stars.iter().map(|s| s.position_at(321));
+        assert_eq!(ocr::recognize_points(points), Ok("LH".to_string()));
+    }
+
+    #[test]
+    fn test_plateau() {
+        let stars: Vec<Star> = [(6, 2, -2, 3), (9, -3, 0, 2), (-8, 3, 2, 1)]
+            .iter()
+            .map(|&(x, y, vx, vy)| Star { x, y, vx, vy })
+            .collect();
+        // flat for a second on the way down to the minimum
+        let areas: Vec<i64> = (0..5).map(|t| bounding_box_area(&stars, t)).collect();
+        assert_eq!(areas, vec![126, 112, 112, 108, 120]);
+        assert_eq!(converge(&stars, Strategy::BinarySearch), 3);
+
+        // moving together, the stars never get any closer
+        let together = rewind(&LH, 50, |_| (2, -1));
+        assert_eq!(converge(&together, Strategy::BinarySearch), 0);
+    }
+}
@@ -9,6 +9,10 @@ use std::str::FromStr;
 
 use regex::Regex;
 
+mod convergence;
+
+use convergence::Strategy;
+
 const POINT_REGEX: &str = r"^position=<\s*(.+),\s*(.+)> velocity=<\s*(.+),\s*(.+)>$";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -20,14 +24,8 @@ struct Star {
 }
 
 impl Star {
-    fn tick_forwards(&mut self) {
-        self.x += self.vx;
-        self.y += self.vy;
-    }
-
-    fn tick_backwards(&mut self) {
-        self.x -= self.vx;
-        self.y -= self.vy;
+    fn position_at(&self, t: i64) -> (i64, i64) {
+        (self.x + self.vx * t, self.y + self.vy * t)
     }
 }
 
@@ -52,31 +50,13 @@ struct State {
 }
 
 impl State {
-    fn tick_forwards(&mut self) {
+    fn advance(&mut self, seconds: usize) {
         for star in self.state.iter_mut() {
-            star.tick_forwards();
+            let (x, y) = star.position_at(seconds as i64);
+            star.x = x;
+            star.y = y;
         }
-        self.time += 1;
-    }
-
-    fn tick_backwards(&mut self) {
-        for star in self.state.iter_mut() {
-            star.tick_backwards();
-        }
-        self.time -= 1;
-    }
-
-    fn centroid(&self) -> (i64, i64) {
-        let x = self.state.iter().map(|s| s.x).sum::<i64>() / self.state.len() as i64;
-        let y = self.state.iter().map(|s| s.y).sum::<i64>() / self.state.len() as i64;
-        (x, y)
-    }
-
-    fn total_distance_to(&self, (x, y): (i64, i64)) -> i64 {
-        self.state
-            .iter()
-            .map(|s| (s.x - x).abs() + (s.y - y).abs())
-            .sum()
+        self.time += seconds;
     }
 }
 
@@ -121,41 +101,37 @@ impl fmt::Display for State {
     }
 }
 
-fn sim(input: &str) -> State {
+fn sim(input: &str, strategy: Strategy) -> State {
     let content = fs::read_to_string(input).unwrap();
     let mut state = State::from_str(&content).unwrap();
 
-    let mut min_total_distance = state.total_distance_to(state.centroid());
-    loop {
-        state.tick_forwards();
-
-        let new_distance = state.total_distance_to(state.centroid());
-
-        if new_distance <= min_total_distance {
-            min_total_distance = new_distance;
-        } else {
-            state.tick_backwards();
-            break;
-        }
-    }
+    let best = convergence::converge(&state.state, strategy);
+    state.advance(best as usize);
 
     state
 }
 
-fn part1(input: &str) {
-    let state = sim(input);
-    println!("{}", state);
+fn part1(input: &str, strategy: Strategy) {
+    let state = sim(input, strategy);
+    match ocr::recognize_points(state.state.iter().map(|s| (s.x, s.y))) {
+        Ok(message) => println!("{}", message),
+        Err(err) => println!("Could not read the message ({}):\n{}", err, state),
+    }
 }
 
-fn part2(input: &str) {
-    let state = sim(input);
+fn part2(input: &str, strategy: Strategy) {
+    let state = sim(input, strategy);
     println!("{}", state.time);
 }
 
 fn main() {
     let args: Vec<_> = env::args().collect();
+    let strategy = match args.get(3).map(String::as_str) {
+        Some("binary") => Strategy::BinarySearch,
+        _ => Strategy::Estimate,
+    };
     match args[1].as_str() {
-        "1" => part1(args[2].as_str()),
-        _ => part2(args[2].as_str()),
+        "1" => part1(args[2].as_str(), strategy),
+        _ => part2(args[2].as_str(), strategy),
     };
 }
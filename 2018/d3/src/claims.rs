@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use crate::Rect;
+
+// Segment tree over the compressed y coordinates, tracking how much of the
+// y axis is covered by at least one and at least two claims.
+struct Coverage {
+    ys: Vec<u32>,
+    count: Vec<u32>,
+    once: Vec<u64>,
+    twice: Vec<u64>,
+}
+
+impl Coverage {
+    fn new(ys: Vec<u32>) -> Self {
+        let nodes = 4 * ys.len().max(1);
+        Coverage {
+            ys,
+            count: vec![0; nodes],
+            once: vec![0; nodes],
+            twice: vec![0; nodes],
+        }
+    }
+
+    fn covered_twice(&self) -> u64 {
+        self.twice[1]
+    }
+
+    // Adds `delta` to the cover count of y in [y0, y1).
+    fn update(&mut self, y0: u32, y1: u32, delta: i32) {
+        let lo = self.ys.binary_search(&y0).unwrap();
+        let hi = self.ys.binary_search(&y1).unwrap();
+        if lo < hi {
+            self.update_node(1, 0, self.ys.len() - 1, lo, hi, delta);
+        }
+    }
+
+    fn update_node(&mut self, node: usize, l: usize, r: usize, lo: usize, hi: usize, delta: i32) {
+        if hi <= l || r <= lo {
+            return;
+        }
+        if lo <= l && r <= hi {
+            self.count[node] = (self.count[node] as i32 + delta) as u32;
+        } else {
+            let mid = (l + r) / 2;
+            self.update_node(2 * node, l, mid, lo, hi, delta);
+            self.update_node(2 * node + 1, mid, r, lo, hi, delta);
+        }
+        self.pull(node, l, r);
+    }
+
+    fn pull(&mut self, node: usize, l: usize, r: usize) {
+        let full = (self.ys[r] - self.ys[l]) as u64;
+        let leaf = r - l == 1;
+        let (child_once, child_twice) = if leaf {
+            (0, 0)
+        } else {
+            (
+                self.once[2 * node] + self.once[2 * node + 1],
+                self.twice[2 * node] + self.twice[2 * node + 1],
+            )
+        };
+        self.once[node] = if self.count[node] > 0 {
+            full
+        } else {
+            child_once
+        };
+        self.twice[node] = match self.count[node] {
+            0 => child_twice,
+            1 => child_once,
+            _ => full,
+        };
+    }
+}
+
+// Square inches covered by two or more claims, sweeping a vertical line
+// across the claims' left and right edges.
+pub fn overlap_area(claims: &[Rect]) -> u64 {
+    let mut ys: Vec<u32> = claims.iter().flat_map(|c| [c.top(), c.bottom()]).collect();
+    ys.sort_unstable();
+    ys.dedup();
+    if ys.len() < 2 {
+        return 0;
+    }
+
+    let mut events: Vec<(u32, i32, &Rect)> = claims
+        .iter()
+        .filter(|c| c.width > 0 && c.height > 0)
+        .flat_map(|c| [(c.left(), 1, c), (c.right(), -1, c)])
+        .collect();
+    events.sort_by_key(|&(x, delta, _)| (x, delta));
+
+    let mut coverage = Coverage::new(ys);
+    let mut area = 0;
+    let mut last_x = events.first().map_or(0, |e| e.0);
+    for (x, delta, claim) in events {
+        area += (x - last_x) as u64 * coverage.covered_twice();
+        coverage.update(claim.top(), claim.bottom(), delta);
+        last_x = x;
+    }
+    area
+}
+
+// Two claims with the same id.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DuplicateId(pub u32);
+
+pub struct Analysis<'a> {
+    claims: &'a [Rect],
+    // claim ids overlapping each claim, by claim id
+    partners: HashMap<u32, Vec<u32>>,
+}
+
+impl<'a> Analysis<'a> {
+    // Claims are visited from left to right, and each one is only compared
+    // with those starting before it ends. Only the x axis narrows things
+    // down, so tall claims sharing the same columns are still all compared
+    // with each other. Empty claims cover nothing, and overlap nothing.
+    pub fn new(claims: &'a [Rect]) -> Result<Self, DuplicateId> {
+        let mut partners: HashMap<u32, Vec<u32>> = HashMap::new();
+        for c in claims {
+            if partners.insert(c.id, Vec::new()).is_some() {
+                return Err(DuplicateId(c.id));
+            }
+        }
+
+        let mut by_left: Vec<&Rect> = claims
+            .iter()
+            .filter(|c| c.width > 0 && c.height > 0)
+            .collect();
+        by_left.sort_by_key(|c| c.left());
+        for (i, a) in by_left.iter().enumerate() {
+            for b in by_left[i + 1..].iter().take_while(|b| b.left() < a.right()) {
+                if a.overlaps(b) {
+                    partners.get_mut(&a.id).unwrap().push(b.id);
+                    partners.get_mut(&b.id).unwrap().push(a.id);
+                }
+            }
+        }
+        for ids in partners.values_mut() {
+            ids.sort_unstable();
+        }
+
+        Ok(Analysis { claims, partners })
+    }
+
+    pub fn partners(&self, id: u32) -> Option<&[u32]> {
+        self.partners.get(&id).map(Vec::as_slice)
+    }
+
+    // Claims not overlapping any other, in input order.
+    pub fn intact(&self) -> Vec<&Rect> {
+        self.claims
+            .iter()
+            .filter(|c| self.partners[&c.id].is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn claims(lines: &[&str]) -> Vec<Rect> {
+        lines.iter().map(|l| Rect::from_str(l).unwrap()).collect()
+    }
+
+    const EXAMPLE: [&str; 3] = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"];
+
+    #[test]
+    fn test_example() {
+        let claims = claims(&EXAMPLE);
+        let analysis = Analysis::new(&claims).unwrap();
+        assert_eq!(overlap_area(&claims), 4);
+        assert_eq!(
+            analysis.intact().iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(analysis.partners(1), Some(&[2][..]));
+        assert_eq!(analysis.partners(3), Some(&[][..]));
+        assert_eq!(analysis.partners(4), None);
+    }
+
+    #[test]
+    fn test_touching_claims_do_not_overlap() {
+        let claims = claims(&["#1 @ 0,0: 2x2", "#2 @ 2,0: 2x2", "#3 @ 0,2: 4x1"]);
+        let analysis = Analysis::new(&claims).unwrap();
+        assert_eq!(overlap_area(&claims), 0);
+        assert_eq!(analysis.intact().len(), 3);
+    }
+
+    #[test]
+    fn test_overlap_counted_once() {
+        // three claims stacked on the same square, plus a far away pair
+        let claims = claims(&[
+            "#1 @ 0,0: 3x3",
+            "#2 @ 1,1: 3x3",
+            "#3 @ 1,1: 1x1",
+            "#4 @ 5000000,5000000: 10x10",
+            "#5 @ 5000005,5000000: 10x10",
+        ]);
+        let analysis = Analysis::new(&claims).unwrap();
+        assert_eq!(overlap_area(&claims), 4 + 50);
+        assert_eq!(analysis.partners(1), Some(&[2, 3][..]));
+        assert_eq!(analysis.partners(5), Some(&[4][..]));
+        assert!(analysis.intact().is_empty());
+    }
+
+    #[test]
+    fn test_empty_claims() {
+        let claims = claims(&["#1 @ 0,0: 4x4", "#2 @ 1,1: 0x2", "#3 @ 1,1: 2x0"]);
+        let analysis = Analysis::new(&claims).unwrap();
+        assert_eq!(analysis.partners(1), Some(&[][..]));
+        assert_eq!(analysis.partners(2), Some(&[][..]));
+        assert_eq!(analysis.intact().len(), 3);
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let claims = claims(&["#1 @ 0,0: 2x2", "#2 @ 5,5: 2x2", "#1 @ 9,9: 2x2"]);
+        assert!(matches!(Analysis::new(&claims), Err(DuplicateId(1))));
+    }
+
+    #[test]
+    fn test_matches_grid() {
+        // pseudo-random claims checked against painting a grid
+        let mut seed = 12345u32;
+        let mut next = |m: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % m
+        };
+        let claims: Vec<Rect> = (1..=60)
+            .map(|id| Rect {
+                id,
+                offset_x: next(40),
+                offset_y: next(40),
+                width: next(12),
+                height: next(12),
+            })
+            .collect();
+
+        let mut grid = vec![0; 64 * 64];
+        for c in &claims {
+            for y in c.top()..c.bottom() {
+                for x in c.left()..c.right() {
+                    grid[(y * 64 + x) as usize] += 1;
+                }
+            }
+        }
+        let expected = grid.iter().filter(|&&n| n > 1).count() as u64;
+        assert_eq!(overlap_area(&claims), expected);
+    }
+}
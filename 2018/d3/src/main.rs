@@ -1,6 +1,3 @@
-extern crate regex;
-
-use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::num::ParseIntError;
@@ -8,23 +5,42 @@ use std::str::FromStr;
 
 use regex::Regex;
 
-const CLOTH_SIZE: usize = 1000;
+mod claims;
+
+use claims::{Analysis, DuplicateId};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Rect {
-    id: u16,
-    offset_x: u16,
-    offset_y: u16,
-    width: u16,
-    height: u16,
+pub struct Rect {
+    id: u32,
+    offset_x: u32,
+    offset_y: u32,
+    width: u32,
+    height: u32,
 }
 
 impl Rect {
-    fn intersects(&self, other: &Rect) -> bool {
-        !(self.offset_x + self.width < other.offset_x
-            || other.offset_x + other.width < self.offset_x
-            || self.offset_y + self.height < other.offset_y
-            || other.offset_y + other.height < self.offset_y)
+    fn left(&self) -> u32 {
+        self.offset_x
+    }
+
+    fn right(&self) -> u32 {
+        self.offset_x + self.width
+    }
+
+    fn top(&self) -> u32 {
+        self.offset_y
+    }
+
+    fn bottom(&self) -> u32 {
+        self.offset_y + self.height
+    }
+
+    // Claims sharing an edge do not overlap.
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.left() < other.right()
+            && other.left() < self.right()
+            && self.top() < other.bottom()
+            && other.top() < self.bottom()
     }
 }
 
@@ -35,60 +51,63 @@ impl FromStr for Rect {
         let re = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
         let captures = re.captures(s).unwrap();
         Ok(Rect {
-            id: captures[1].parse::<u16>()?,
-            offset_x: captures[2].parse::<u16>()?,
-            offset_y: captures[3].parse::<u16>()?,
-            width: captures[4].parse::<u16>()?,
-            height: captures[5].parse::<u16>()?,
+            id: captures[1].parse::<u32>()?,
+            offset_x: captures[2].parse::<u32>()?,
+            offset_y: captures[3].parse::<u32>()?,
+            width: captures[4].parse::<u32>()?,
+            height: captures[5].parse::<u32>()?,
         })
     }
 }
 
-fn pos(x: u16, y: u16) -> usize {
-    CLOTH_SIZE * (y as usize) + (x as usize)
+fn read_claims() -> Vec<Rect> {
+    let content = fs::read_to_string("input.txt").unwrap();
+    content
+        .lines()
+        .map(Rect::from_str)
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn part1() {
+    println!("{}", claims::overlap_area(&read_claims()));
 }
 
-fn occupy_area(cloth: &mut Vec<u16>, rect: &Rect) {
-    for y in 0..rect.height {
-        for x in 0..rect.width {
-            cloth[pos(rect.offset_x + x, rect.offset_y + y)] += 1;
+fn analyse(claims: &[Rect]) -> Option<Analysis<'_>> {
+    match Analysis::new(claims) {
+        Ok(analysis) => Some(analysis),
+        Err(DuplicateId(id)) => {
+            println!("Claim #{} appears more than once.", id);
+            None
         }
     }
 }
 
-fn part1() {
-    let mut cloth: Vec<u16> = vec![0; CLOTH_SIZE * CLOTH_SIZE];
-    let content = fs::read_to_string("input.txt").unwrap();
-    for line in content.lines() {
-        let rect = Rect::from_str(&line).unwrap();
-        occupy_area(&mut cloth, &rect);
+fn part2() {
+    let claims = read_claims();
+    if let Some(analysis) = analyse(&claims) {
+        for claim in analysis.intact() {
+            println!("{}", claim.id);
+        }
     }
-
-    println!("{}", cloth.iter().filter(|&&x| x > 1).count());
 }
 
-fn part2() {
-    let content = fs::read_to_string("input.txt").unwrap();
-    let mut candidates: HashSet<Rect> = content
-        .lines()
-        .map(Rect::from_str)
-        .map(Result::unwrap)
-        .collect();
-    let candidates2: Vec<Rect> = candidates.iter().cloned().collect();
-    for r1 in candidates2.iter() {
-        for r2 in candidates2.iter() {
-            if r1 != r2 && r1.intersects(r2) {
-                candidates.remove(r1);
-                candidates.remove(r2);
-            }
+fn partners(id: &str) {
+    let claims = read_claims();
+    if let Some(analysis) = analyse(&claims) {
+        match analysis.partners(id.parse().unwrap()) {
+            Some(ids) => println!("{:?}", ids),
+            None => println!("No claim #{}.", id),
         }
     }
-    println!("{}", candidates.iter().next().unwrap().id);
 }
 
 fn main() {
-    match env::args().find(|arg| arg == "1") {
-        Some(_) => part1(),
-        None => part2(),
-    };
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("1") => part1(),
+        Some("2") => part2(),
+        Some("partners") if args.len() > 2 => partners(&args[2]),
+        _ => println!("Must pass either '1', '2' or 'partners <id>'."),
+    }
 }
@@ -1,15 +1,15 @@
-extern crate chrono;
-extern crate regex;
-
 use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::fs;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
-use chrono::{DateTime, ParseError, TimeZone, Timelike, Utc};
-use regex::Regex;
+use chrono::{NaiveDateTime, ParseError};
+
+mod shifts;
+
+use shifts::{Analysis, AnalysisErr};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum Log {
@@ -20,13 +20,13 @@ enum Log {
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct LogEntry {
-    datetime: DateTime<Utc>,
+    datetime: NaiveDateTime,
     log: Log,
 }
 
 impl PartialOrd for LogEntry {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.datetime.cmp(&other.datetime))
+        Some(self.cmp(other))
     }
 }
 
@@ -37,151 +37,184 @@ impl Ord for LogEntry {
 }
 
 #[derive(Debug)]
-struct LogParseErr {}
+enum LogParseErrKind {
+    Format,
+    Date(ParseError),
+    GuardId(ParseIntError),
+}
+
+#[derive(Debug)]
+struct LogParseErr {
+    line: String,
+    kind: LogParseErrKind,
+}
+
+impl fmt::Display for LogParseErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            LogParseErrKind::Format => write!(f, "malformed log {:?}", self.line),
+            LogParseErrKind::Date(err) => write!(f, "invalid date in {:?}: {}", self.line, err),
+            LogParseErrKind::GuardId(err) => {
+                write!(f, "invalid guard id in {:?}: {}", self.line, err)
+            }
+        }
+    }
+}
 
-impl From<ParseIntError> for LogParseErr {
-    fn from(_: ParseIntError) -> LogParseErr {
-        LogParseErr {}
+impl From<ParseIntError> for LogParseErrKind {
+    fn from(err: ParseIntError) -> LogParseErrKind {
+        LogParseErrKind::GuardId(err)
     }
 }
 
-impl From<ParseError> for LogParseErr {
-    fn from(_: ParseError) -> LogParseErr {
-        LogParseErr {}
+impl From<ParseError> for LogParseErrKind {
+    fn from(err: ParseError) -> LogParseErrKind {
+        LogParseErrKind::Date(err)
     }
 }
 
+fn parse_log(s: &str) -> Result<LogEntry, LogParseErrKind> {
+    let (stamp, text) = s
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        .ok_or(LogParseErrKind::Format)?;
+    let datetime = NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M")?;
+    let log = match text {
+        "falls asleep" => Log::FallsAsleep,
+        "wakes up" => Log::WakesUp,
+        _ => {
+            let id = text
+                .strip_prefix("Guard #")
+                .and_then(|rest| rest.split_ascii_whitespace().next())
+                .ok_or(LogParseErrKind::Format)?;
+            Log::NewShift(id.parse::<u16>()?)
+        }
+    };
+
+    Ok(LogEntry { datetime, log })
+}
+
 impl FromStr for LogEntry {
     type Err = LogParseErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let caps = Regex::new(r"^\[(.+)\] (.+)$").unwrap().captures(s).unwrap();
-        let datetime = Utc.datetime_from_str(&caps[1], "%Y-%m-%d %H:%M")?;
-        let log_type = if caps[2].starts_with("falls") {
-            Log::FallsAsleep
-        } else if caps[2].starts_with("wakes") {
-            Log::WakesUp
-        } else {
-            let id = Regex::new(r"^Guard #(\d+)")
-                .unwrap()
-                .captures(&caps[2])
-                .unwrap()[1]
-                .parse::<u16>()?;
-            Log::NewShift(id)
-        };
-
-        Ok(LogEntry {
-            datetime,
-            log: log_type,
+        parse_log(s).map_err(|kind| LogParseErr {
+            line: s.to_string(),
+            kind,
         })
     }
 }
 
-#[derive(Clone)]
-enum GuardState {
-    JustArrived(DateTime<Utc>),
-    Awoken(DateTime<Utc>),
-    Sleeping(DateTime<Utc>),
+fn parse_logs(content: &str) -> Result<Vec<LogEntry>, LogParseErr> {
+    content.lines().map(LogEntry::from_str).collect()
 }
 
-#[derive(Clone)]
-struct Guard {
-    id: u16,
-    states: Vec<GuardState>,
-    minutes: Vec<u16>,
+fn analyse() -> Result<Analysis, AnalysisErr> {
+    let content = fs::read_to_string("input.txt").unwrap();
+    let logs = parse_logs(&content).unwrap_or_else(|err| panic!("{}", err));
+    Analysis::from_logs(logs)
 }
 
-fn time_to_pos<T: TimeZone>(datetime: &DateTime<T>) -> usize {
-    datetime.minute() as usize
+fn part1(analysis: &Analysis) {
+    let (id, minute) = analysis.sleepiest_guard().unwrap();
+    println!("{}", id as usize * minute);
 }
 
-impl Guard {
-    fn new(id: u16) -> Guard {
-        Guard {
-            id,
-            states: Vec::new(),
-            // From 23:00 to 00:59
-            minutes: vec![0; 60],
-        }
-    }
+fn part2(analysis: &Analysis) {
+    let (id, minute) = analysis.most_regular_guard().unwrap();
+    println!("{}", id as usize * minute);
+}
 
-    fn push_state(&mut self, log_entry: &LogEntry) {
-        let state = match &log_entry.log {
-            Log::NewShift(_) => GuardState::JustArrived(log_entry.datetime),
-            Log::WakesUp => GuardState::Awoken(log_entry.datetime),
-            Log::FallsAsleep => GuardState::Sleeping(log_entry.datetime),
-        };
-        self.states.push(state);
+fn top(analysis: &Analysis, k: usize) {
+    for guard in analysis.top_guards(k) {
+        let (minute, times) = guard.most_asleep_minute();
+        println!(
+            "#{}: {} minutes asleep, {} times on minute {}",
+            guard.id, guard.total, times, minute
+        );
     }
+}
 
-    fn set_minutes(&mut self) {
-        for (before, after) in self.states.iter().zip(self.states[1..].iter()) {
-            if let (GuardState::Sleeping(start), GuardState::Awoken(end)) = (before, after) {
-                for i in time_to_pos(start)..time_to_pos(end) {
-                    self.minutes[i] += 1;
-                }
-            }
-        }
+fn longest_nap(analysis: &Analysis) {
+    if let Some(nap) = analysis.longest_nap() {
+        println!(
+            "#{}: {} minutes, from {} to {}",
+            nap.guard,
+            nap.minutes(),
+            nap.start,
+            nap.end
+        );
     }
 }
 
-fn find_most_asleep_minute(guard: &Guard) -> usize {
-    let (min, _) = guard
-        .minutes
-        .iter()
-        .enumerate()
-        .max_by_key(|&(_, i)| i)
-        .unwrap();
-    min
+fn histogram(analysis: &Analysis) {
+    for (minute, times) in analysis.minute_histogram().iter().enumerate() {
+        println!("00:{:02} {}", minute, "#".repeat(*times as usize));
+    }
 }
 
-fn go<K, F>(guard_sort_key: F)
-where
-    K: Ord,
-    F: for<'a> FnMut(&'a &Guard) -> K,
-{
-    let content = fs::read_to_string("input.txt").unwrap();
-    let mut logs: Vec<LogEntry> = content
-        .lines()
-        .map(|l| LogEntry::from_str(l).unwrap())
-        .collect::<Vec<LogEntry>>();
-    logs.sort();
-
-    let mut guards: HashMap<u16, Guard> = HashMap::new();
-    let mut current_gid: Option<u16> = None;
-    for log in logs.iter() {
-        if let Log::NewShift(id) = log.log {
-            current_gid = Some(id);
-        }
-
-        (*guards
-            .entry(current_gid.unwrap())
-            .or_insert_with(|| Guard::new(current_gid.unwrap())))
-        .push_state(&log);
+fn guard(analysis: &Analysis, id: u16) {
+    match analysis.guard(id) {
+        Some(guard) => println!(
+            "#{}: {} minutes asleep over {} naps",
+            guard.id,
+            guard.total,
+            guard.naps.len()
+        ),
+        None => println!("No guard #{}", id),
     }
-
-    guards.values_mut().for_each(Guard::set_minutes);
-
-    let most_asleep_guard = guards.values().max_by_key(guard_sort_key).unwrap();;
-    let most_asleep_minute = find_most_asleep_minute(&most_asleep_guard);
-    println!(
-        "{}",
-        (most_asleep_guard.id as usize) * (most_asleep_minute % 60)
-    );
 }
 
-fn part1() {
-    go(|g| g.minutes.iter().sum::<u16>());
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let analysis = match analyse() {
+        Ok(analysis) => analysis,
+        Err(err) => {
+            println!("Inconsistent logs: {}", err);
+            return;
+        }
+    };
+    match args.get(1).map(String::as_str) {
+        Some("1") => part1(&analysis),
+        Some("top") => top(&analysis, args.get(2).map_or(3, |k| k.parse().unwrap())),
+        Some("nap") => longest_nap(&analysis),
+        Some("histogram") => histogram(&analysis),
+        Some("guard") => guard(&analysis, args[2].parse().unwrap()),
+        _ => part2(&analysis),
+    };
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn part2() {
-    go(|g| *g.minutes.iter().max().unwrap());
-}
+    #[test]
+    fn test_parse_log() {
+        assert_eq!(
+            LogEntry::from_str("[1518-11-01 00:00] Guard #10 begins shift").unwrap(),
+            LogEntry {
+                datetime: NaiveDateTime::parse_from_str("1518-11-01 00:00", "%Y-%m-%d %H:%M")
+                    .unwrap(),
+                log: Log::NewShift(10),
+            }
+        );
+    }
 
-fn main() {
-    match env::args().find(|arg| arg == "1") {
-        Some(_) => part1(),
-        None => part2(),
-    };
+    #[test]
+    fn test_parse_errors() {
+        let err =
+            parse_logs("[1518-11-01 00:05] falls asleep\n[1518-11-01 00:05 wakes up").unwrap_err();
+        assert_eq!(err.line, "[1518-11-01 00:05 wakes up");
+        assert!(matches!(err.kind, LogParseErrKind::Format));
+
+        let err = LogEntry::from_str("[1518-13-01 00:05] wakes up").unwrap_err();
+        assert!(matches!(err.kind, LogParseErrKind::Date(_)));
+
+        let err = LogEntry::from_str("[1518-11-01 00:05] Guard #x begins shift").unwrap_err();
+        assert!(matches!(err.kind, LogParseErrKind::GuardId(_)));
+        assert_eq!(
+            err.to_string(),
+            "invalid guard id in \"[1518-11-01 00:05] Guard #x begins shift\": invalid digit found in string"
+        );
+    }
 }
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{Duration, NaiveDateTime, Timelike};
+
+use crate::{Log, LogEntry};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nap {
+    pub guard: u16,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Nap {
+    pub fn minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+
+    // minute of the hour of every minute slept, so naps may cross the hour
+    // or midnight
+    fn minutes_of_hour(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.minutes()).map(move |m| (self.start + Duration::minutes(m)).minute() as usize)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GuardStats {
+    pub id: u16,
+    pub total: u32,
+    pub minutes: [u32; 60],
+    pub naps: Vec<Nap>,
+}
+
+impl GuardStats {
+    fn new(id: u16) -> Self {
+        GuardStats {
+            id,
+            total: 0,
+            minutes: [0; 60],
+            naps: Vec::new(),
+        }
+    }
+
+    fn push_nap(&mut self, nap: Nap) {
+        for minute in nap.minutes_of_hour() {
+            self.minutes[minute] += 1;
+            self.total += 1;
+        }
+        self.naps.push(nap);
+    }
+
+    // (minute, times asleep on that minute), preferring the earliest minute
+    pub fn most_asleep_minute(&self) -> (usize, u32) {
+        self.minutes
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by_key(|&(minute, times)| (times, std::cmp::Reverse(minute)))
+            .unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnalysisErr {
+    NoGuardOnDuty(NaiveDateTime),
+    StillAsleep(NaiveDateTime),
+    AlreadyAsleep(NaiveDateTime),
+    NotAsleep(NaiveDateTime),
+}
+
+impl fmt::Display for AnalysisErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalysisErr::NoGuardOnDuty(at) => write!(f, "{}: no guard is on duty", at),
+            AnalysisErr::StillAsleep(at) => write!(f, "{}: the guard is still asleep", at),
+            AnalysisErr::AlreadyAsleep(at) => write!(f, "{}: the guard is already asleep", at),
+            AnalysisErr::NotAsleep(at) => write!(f, "{}: the guard is not asleep", at),
+        }
+    }
+}
+
+pub struct Analysis {
+    guards: HashMap<u16, GuardStats>,
+}
+
+impl Analysis {
+    // Logs may come in any order.
+    pub fn from_logs(mut logs: Vec<LogEntry>) -> Result<Analysis, AnalysisErr> {
+        logs.sort();
+
+        let mut guards: HashMap<u16, GuardStats> = HashMap::new();
+        let mut on_duty: Option<u16> = None;
+        let mut asleep_since: Option<NaiveDateTime> = None;
+
+        for entry in logs {
+            let at = entry.datetime;
+            match (entry.log, on_duty, asleep_since) {
+                (Log::NewShift(id), _, None) => {
+                    guards.entry(id).or_insert_with(|| GuardStats::new(id));
+                    on_duty = Some(id);
+                }
+                (Log::NewShift(_), _, Some(_)) => return Err(AnalysisErr::StillAsleep(at)),
+                (_, None, _) => return Err(AnalysisErr::NoGuardOnDuty(at)),
+                (Log::FallsAsleep, _, None) => asleep_since = Some(at),
+                (Log::FallsAsleep, _, Some(_)) => return Err(AnalysisErr::AlreadyAsleep(at)),
+                (Log::WakesUp, Some(guard), Some(start)) => {
+                    let nap = Nap {
+                        guard,
+                        start,
+                        end: at,
+                    };
+                    guards.get_mut(&guard).unwrap().push_nap(nap);
+                    asleep_since = None;
+                }
+                (Log::WakesUp, _, None) => return Err(AnalysisErr::NotAsleep(at)),
+            }
+        }
+        // the last nap never ended
+        if let Some(start) = asleep_since {
+            return Err(AnalysisErr::StillAsleep(start));
+        }
+
+        Ok(Analysis { guards })
+    }
+
+    pub fn guard(&self, id: u16) -> Option<&GuardStats> {
+        self.guards.get(&id)
+    }
+
+    // Guards sorted by total minutes asleep, sleepiest first.
+    pub fn top_guards(&self, k: usize) -> Vec<&GuardStats> {
+        let mut guards: Vec<&GuardStats> = self.guards.values().collect();
+        guards.sort_by_key(|g| (std::cmp::Reverse(g.total), g.id));
+        guards.truncate(k);
+        guards
+    }
+
+    // How many times any guard was asleep on each minute of the hour.
+    pub fn minute_histogram(&self) -> [u32; 60] {
+        let mut histogram = [0; 60];
+        for guard in self.guards.values() {
+            for (total, times) in histogram.iter_mut().zip(guard.minutes.iter()) {
+                *total += times;
+            }
+        }
+        histogram
+    }
+
+    pub fn longest_nap(&self) -> Option<&Nap> {
+        self.guards
+            .values()
+            .flat_map(|g| g.naps.iter())
+            .max_by_key(|nap| (nap.minutes(), std::cmp::Reverse(nap.start)))
+    }
+
+    // Strategy 1: the guard asleep the most, and the minute they sleep the most.
+    pub fn sleepiest_guard(&self) -> Option<(u16, usize)> {
+        self.top_guards(1)
+            .first()
+            .map(|g| (g.id, g.most_asleep_minute().0))
+    }
+
+    // Strategy 2: the guard most frequently asleep on the same minute.
+    pub fn most_regular_guard(&self) -> Option<(u16, usize)> {
+        self.guards
+            .values()
+            .map(|g| (g.most_asleep_minute(), g.id))
+            .max_by_key(|&((minute, times), id)| (times, std::cmp::Reverse((id, minute))))
+            .map(|((minute, _), id)| (id, minute))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_logs;
+
+    const EXAMPLE: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    fn analysis(s: &str) -> Analysis {
+        Analysis::from_logs(parse_logs(s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let analysis = analysis(EXAMPLE);
+        assert_eq!(analysis.sleepiest_guard(), Some((10, 24)));
+        assert_eq!(analysis.most_regular_guard(), Some((99, 45)));
+    }
+
+    #[test]
+    fn test_unsorted() {
+        let mut lines: Vec<&str> = EXAMPLE.lines().collect();
+        lines.reverse();
+        let analysis = analysis(&lines.join("\n"));
+        assert_eq!(analysis.sleepiest_guard(), Some((10, 24)));
+    }
+
+    #[test]
+    fn test_queries() {
+        let analysis = analysis(EXAMPLE);
+        assert_eq!(
+            analysis
+                .top_guards(5)
+                .iter()
+                .map(|g| (g.id, g.total))
+                .collect::<Vec<_>>(),
+            vec![(10, 50), (99, 30)]
+        );
+        assert_eq!(analysis.guard(99).unwrap().minutes[45], 3);
+        assert_eq!(analysis.minute_histogram()[24], 2);
+
+        let nap = analysis.longest_nap().unwrap();
+        assert_eq!((nap.guard, nap.minutes()), (10, 25));
+    }
+
+    #[test]
+    fn test_nap_across_the_hour() {
+        let analysis = analysis(
+            "[1518-11-01 23:50] Guard #7 begins shift
+[1518-11-01 23:55] falls asleep
+[1518-11-02 00:03] wakes up",
+        );
+        let guard = analysis.guard(7).unwrap();
+        assert_eq!(guard.total, 8);
+        assert_eq!(guard.minutes[59], 1);
+        assert_eq!(guard.minutes[2], 1);
+        assert_eq!(guard.minutes[3], 0);
+    }
+
+    #[test]
+    fn test_inconsistent_logs() {
+        let logs = parse_logs("[1518-11-01 00:05] falls asleep").unwrap();
+        assert!(matches!(
+            Analysis::from_logs(logs),
+            Err(AnalysisErr::NoGuardOnDuty(_))
+        ));
+        let logs =
+            parse_logs("[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] wakes up")
+                .unwrap();
+        assert!(matches!(
+            Analysis::from_logs(logs),
+            Err(AnalysisErr::NotAsleep(_))
+        ));
+        let logs = parse_logs(
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep",
+        )
+        .unwrap();
+        let err = Analysis::from_logs(logs).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "1518-11-01 00:05:00: the guard is still asleep"
+        );
+    }
+}
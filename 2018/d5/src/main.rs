@@ -1,62 +1,34 @@
-use std::collections::HashSet;
 use std::env;
 use std::fs;
 
-fn react(polymer: &[u8]) -> usize {
-    let mut right: Vec<u8> = polymer.to_vec();
-    let mut left: Vec<u8> = Vec::with_capacity(right.len());
+mod polymer;
 
-    loop {
-        match (left.pop(), right.pop()) {
-            (None, Some(next)) => {
-                left.push(next);
-            }
-            (Some(top), Some(next)) => {
-                if top ^ next != 32 {
-                    left.push(top);
-                    left.push(next);
-                }
-            }
+use polymer::Polymer;
 
-            (Some(top), None) => {
-                left.push(top);
-                break;
-            }
-            (None, None) => break,
-        }
-    }
-    left.len()
+fn read_polymer() -> Polymer {
+    let content = fs::read_to_string("input.txt").unwrap();
+    Polymer::react(content.trim().as_bytes())
 }
 
 fn part1() {
-    let content = fs::read_to_string("input.txt").unwrap();
-    println!("{}", react(&content.bytes().collect::<Vec<u8>>()));
+    println!("{}", read_polymer().len());
 }
 
 fn part2() {
-    let content = fs::read_to_string("input.txt").unwrap();
-    let bytes: Vec<u8> = content.bytes().collect();
-    let candidates: HashSet<u8> = content.as_str().to_lowercase().bytes().collect();
-
-    let min = candidates
-        .iter()
-        .map(|candidate| {
-            react(
-                &bytes
-                    .iter()
-                    .cloned()
-                    .filter(|c| c != candidate && *c != candidate ^ 32)
-                    .collect::<Vec<u8>>(),
-            )
-        })
-        .min()
-        .unwrap();
-    println!("{}", min);
+    let removal = polymer::best_removal(&read_polymer()).unwrap();
+    println!("{}", removal.polymer.len());
+    println!(
+        "removing {}/{}",
+        removal.unit as char,
+        removal.unit.to_ascii_uppercase() as char
+    );
 }
 
 fn main() {
-    match env::args().find(|arg| arg == "1") {
-        Some(_) => part1(),
-        None => part2(),
-    };
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("1") => part1(),
+        Some("2") => part2(),
+        _ => println!("Must pass either '1' or '2'."),
+    }
 }
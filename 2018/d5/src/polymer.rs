@@ -0,0 +1,126 @@
+use std::fmt;
+use std::thread;
+
+fn reacts(a: u8, b: u8) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
+}
+
+// A fully reduced polymer: no two adjacent units react.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Polymer(Vec<u8>);
+
+impl Polymer {
+    pub fn react(units: &[u8]) -> Polymer {
+        let mut polymer = Polymer(Vec::with_capacity(units.len()));
+        polymer.extend(units);
+        polymer
+    }
+
+    // Appends units to the end of the polymer, reacting them with what is
+    // already there, so a polymer can be reduced piece by piece.
+    pub fn extend(&mut self, units: &[u8]) {
+        for &unit in units {
+            match self.0.last() {
+                Some(&top) if reacts(top, unit) => {
+                    self.0.pop();
+                }
+                _ => self.0.push(unit),
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    // Unit types present, in lowercase.
+    pub fn unit_types(&self) -> Vec<u8> {
+        let mut types: Vec<u8> = self.0.iter().map(u8::to_ascii_lowercase).collect();
+        types.sort_unstable();
+        types.dedup();
+        types
+    }
+
+    // Removing a unit type from the original polymer and reacting it gives
+    // the same result as removing it from the reduced one.
+    pub fn without(&self, unit: u8) -> Polymer {
+        let mut polymer = Polymer(Vec::with_capacity(self.len()));
+        for chunk in self.0.split(|u| u.eq_ignore_ascii_case(&unit)) {
+            polymer.extend(chunk);
+        }
+        polymer
+    }
+}
+
+impl fmt::Display for Polymer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Removal {
+    pub unit: u8,
+    pub polymer: Polymer,
+}
+
+// Tries removing every unit type in parallel, and keeps the one that leaves
+// the shortest polymer (the first unit in alphabetical order on ties).
+pub fn best_removal(polymer: &Polymer) -> Option<Removal> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = polymer
+            .unit_types()
+            .into_iter()
+            .map(|unit| {
+                scope.spawn(move || Removal {
+                    unit,
+                    polymer: polymer.without(unit),
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .min_by_key(|removal| (removal.polymer.len(), removal.unit))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u8] = b"dabAcCaCBAcCcaDA";
+
+    #[test]
+    fn test_react() {
+        assert_eq!(Polymer::react(EXAMPLE).to_string(), "dabCBAcaDA");
+        assert_eq!(Polymer::react(b"aA").len(), 0);
+        assert_eq!(Polymer::react(b"abBA").len(), 0);
+        assert_eq!(Polymer::react(b"aabAAB").to_string(), "aabAAB");
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut polymer = Polymer::react(b"dabAcCaC");
+        polymer.extend(b"BAcCcaDA");
+        assert_eq!(polymer, Polymer::react(EXAMPLE));
+    }
+
+    #[test]
+    fn test_without() {
+        let polymer = Polymer::react(EXAMPLE);
+        assert_eq!(polymer.without(b'a').to_string(), "dbCBcD");
+        assert_eq!(polymer.without(b'b').to_string(), "daCAcaDA");
+        assert_eq!(polymer.without(b'c').to_string(), "daDA");
+        assert_eq!(polymer.without(b'd').to_string(), "abCBAc");
+    }
+
+    #[test]
+    fn test_best_removal() {
+        let removal = best_removal(&Polymer::react(EXAMPLE)).unwrap();
+        assert_eq!(removal.unit, b'c');
+        assert_eq!(removal.polymer.to_string(), "daDA");
+        assert_eq!(best_removal(&Polymer::default()), None);
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::VecDeque;
+
+use crate::Point;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    Site(usize),
+    // equally close to two or more sites
+    Tied,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Area {
+    Finite(usize),
+    Infinite,
+}
+
+// Closest site of every cell in the sites' bounding box.
+#[derive(Debug)]
+pub struct Regions {
+    min_x: usize,
+    min_y: usize,
+    width: usize,
+    height: usize,
+    sites: Vec<Point>,
+    cells: Vec<Owner>,
+}
+
+impl Regions {
+    // Multi-source BFS from all sites at once. A cell's closest sites are the
+    // union of those of its neighbours one step closer, so a cell is only
+    // owned by a site if every such neighbour is.
+    pub fn new(sites: &[Point]) -> Option<Regions> {
+        let min_x = sites.iter().map(|p| p.0).min()?;
+        let min_y = sites.iter().map(|p| p.1).min()?;
+        let width = sites.iter().map(|p| p.0).max()? - min_x + 1;
+        let height = sites.iter().map(|p| p.1).max()? - min_y + 1;
+
+        let mut distances = vec![usize::MAX; width * height];
+        let mut cells = vec![Owner::Tied; width * height];
+        let mut queue = VecDeque::new();
+
+        for (id, site) in sites.iter().enumerate() {
+            let pos = (site.1 - min_y) * width + (site.0 - min_x);
+            if distances[pos] == 0 {
+                cells[pos] = Owner::Tied;
+            } else {
+                distances[pos] = 0;
+                cells[pos] = Owner::Site(id);
+                queue.push_back(pos);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            let (x, y) = (pos % width, pos / width);
+            let neighbours = [
+                (x > 0).then(|| pos - 1),
+                (x + 1 < width).then(|| pos + 1),
+                (y > 0).then(|| pos - width),
+                (y + 1 < height).then(|| pos + width),
+            ];
+            for next in neighbours.iter().flatten().copied() {
+                if distances[next] == usize::MAX {
+                    distances[next] = distances[pos] + 1;
+                    cells[next] = cells[pos];
+                    queue.push_back(next);
+                } else if distances[next] == distances[pos] + 1 && cells[next] != cells[pos] {
+                    cells[next] = Owner::Tied;
+                }
+            }
+        }
+
+        Some(Regions {
+            min_x,
+            min_y,
+            width,
+            height,
+            sites: sites.to_vec(),
+            cells,
+        })
+    }
+
+    pub fn owner(&self, x: usize, y: usize) -> Option<Owner> {
+        if x < self.min_x || y < self.min_y {
+            return None;
+        }
+        let (x, y) = (x - self.min_x, y - self.min_y);
+        if x < self.width && y < self.height {
+            Some(self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    fn on_border(&self, pos: usize) -> bool {
+        let (x, y) = (pos % self.width, pos / self.width);
+        x == 0 || y == 0 || x + 1 == self.width || y + 1 == self.height
+    }
+
+    // Past the bounding box every site gets farther away at the same rate, so
+    // a site owning a cell on its border owns everything straight outwards.
+    pub fn areas(&self) -> Vec<Area> {
+        let mut areas = vec![Area::Finite(0); self.sites.len()];
+        for (pos, &owner) in self.cells.iter().enumerate() {
+            if let Owner::Site(id) = owner {
+                areas[id] = match areas[id] {
+                    Area::Finite(_) if self.on_border(pos) => Area::Infinite,
+                    Area::Finite(n) => Area::Finite(n + 1),
+                    Area::Infinite => Area::Infinite,
+                };
+            }
+        }
+        areas
+    }
+
+    // (site, area) of the largest finite region.
+    pub fn largest_finite(&self) -> Option<(usize, usize)> {
+        self.areas()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, area)| match area {
+                Area::Finite(n) => Some((id, n)),
+                Area::Infinite => None,
+            })
+            .max_by_key(|&(id, n)| (n, std::cmp::Reverse(id)))
+    }
+
+    // Map of the bounding box with each cell labelled by its closest site,
+    // sites drawn as '@' and ties as '.', followed by a legend.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for y in self.min_y..self.min_y + self.height {
+            for x in self.min_x..self.min_x + self.width {
+                let c = match self.owner(x, y).unwrap() {
+                    Owner::Site(id) if self.sites[id] == Point(x, y) => '@',
+                    Owner::Site(id) => label(id),
+                    Owner::Tied => '.',
+                };
+                out.push(c);
+            }
+            out.push('\n');
+        }
+
+        for (id, area) in self.areas().into_iter().enumerate() {
+            let Point(x, y) = self.sites[id];
+            let area = match area {
+                Area::Finite(n) => n.to_string(),
+                Area::Infinite => "infinite".to_string(),
+            };
+            out.push_str(&format!("{} ({}, {}): {}\n", label(id), x, y, area));
+        }
+        out
+    }
+}
+
+const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+fn label(id: usize) -> char {
+    LABELS[id % LABELS.len()] as char
+}
+
+// Sum of the distances to `coords` of every position in lo..=hi, updated
+// from one position to the next by how many coordinates lie on each side.
+fn distance_sums(coords: &[usize], lo: i64, hi: i64) -> Vec<usize> {
+    let mut sorted: Vec<i64> = coords.iter().map(|&c| c as i64).collect();
+    sorted.sort_unstable();
+
+    let mut sum: i64 = sorted.iter().map(|&c| (c - lo).abs()).sum();
+    let mut before = sorted.iter().filter(|&&c| c <= lo).count() as i64;
+    let mut sums = Vec::with_capacity((hi - lo + 1) as usize);
+    for pos in lo..=hi {
+        sums.push(sum as usize);
+        sum += before - (sorted.len() as i64 - before);
+        while (before as usize) < sorted.len() && sorted[before as usize] <= pos + 1 {
+            before += 1;
+        }
+    }
+    sums
+}
+
+// Number of cells whose total distance to all sites is below `threshold`.
+// The distance splits into an x and a y part, so this only needs the sums
+// along each axis, not a grid; the region may reach past the bounding box.
+pub fn safe_region_size(sites: &[Point], threshold: usize) -> usize {
+    if sites.is_empty() {
+        return 0;
+    }
+    // each step away from the bounding box adds one per site
+    let margin = (threshold / sites.len()) as i64 + 1;
+    let axis = |coords: Vec<usize>| {
+        let lo = *coords.iter().min().unwrap() as i64 - margin;
+        let hi = *coords.iter().max().unwrap() as i64 + margin;
+        distance_sums(&coords, lo, hi)
+    };
+    let xs = axis(sites.iter().map(|p| p.0).collect());
+    let mut ys = axis(sites.iter().map(|p| p.1).collect());
+    ys.sort_unstable();
+
+    xs.iter()
+        .filter(|&&x| x < threshold)
+        .map(|&x| ys.partition_point(|&y| y < threshold - x))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Point> {
+        [(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]
+            .iter()
+            .map(|&(x, y)| Point(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_areas() {
+        let regions = Regions::new(&example()).unwrap();
+        assert_eq!(
+            regions.areas(),
+            vec![
+                Area::Infinite,
+                Area::Infinite,
+                Area::Infinite,
+                Area::Finite(9),
+                Area::Finite(17),
+                Area::Infinite,
+            ]
+        );
+        assert_eq!(regions.largest_finite(), Some((4, 17)));
+    }
+
+    #[test]
+    fn test_owner() {
+        let regions = Regions::new(&example()).unwrap();
+        assert_eq!(regions.owner(1, 1), Some(Owner::Site(0)));
+        assert_eq!(regions.owner(5, 1), Some(Owner::Tied));
+        assert_eq!(regions.owner(4, 3), Some(Owner::Site(3)));
+        assert_eq!(regions.owner(0, 0), None);
+        assert_eq!(regions.owner(9, 9), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let sites = [Point(0, 0), Point(4, 0), Point(2, 2)];
+        let map = Regions::new(&sites).unwrap().render();
+        assert_eq!(
+            map,
+            "@A.B@\nA.C.B\n.C@C.\nA (0, 0): infinite\nB (4, 0): infinite\nC (2, 2): infinite\n"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_sites() {
+        let regions = Regions::new(&[Point(2, 2), Point(2, 2), Point(4, 2)]).unwrap();
+        assert_eq!(regions.owner(2, 2), Some(Owner::Tied));
+        assert_eq!(regions.owner(4, 2), Some(Owner::Site(2)));
+        assert!(Regions::new(&[]).is_none());
+    }
+
+    #[test]
+    fn test_safe_region() {
+        assert_eq!(safe_region_size(&example(), 32), 16);
+        // a lone site's region is a diamond reaching past the bounding box
+        assert_eq!(safe_region_size(&[Point(0, 0)], 3), 13);
+        assert_eq!(safe_region_size(&[], 10), 0);
+    }
+}
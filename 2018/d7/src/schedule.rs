@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use dag::Dag;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScheduleErr<K> {
+    NoWorkers,
+    // the steps along the cycle, the first one repeated at the end
+    Cycle(Vec<K>),
+}
+
+impl<K: fmt::Display> fmt::Display for ScheduleErr<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleErr::NoWorkers => write!(f, "no workers to do the steps"),
+            ScheduleErr::Cycle(steps) => {
+                let steps: Vec<String> = steps.iter().map(K::to_string).collect();
+                write!(f, "steps depend on each other: {}", steps.join(" -> "))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slot<K> {
+    pub worker: usize,
+    pub task: K,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Slots sorted by start time, then worker.
+#[derive(Debug)]
+pub struct Timeline<K>(Vec<Slot<K>>);
+
+impl<K: fmt::Display> Timeline<K> {
+    pub fn total_time(&self) -> usize {
+        self.0.iter().map(|slot| slot.end).max().unwrap_or(0)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("worker,task,start,end\n");
+        for slot in &self.0 {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                slot.worker, slot.task, slot.start, slot.end
+            ));
+        }
+        csv
+    }
+}
+
+// Simulates `workers` workers taking the available step that comes first
+// as soon as they are free, lowest numbered worker first.
+pub fn schedule<K, E, F>(
+    graph: &Dag<K, E>,
+    workers: usize,
+    duration: F,
+) -> Result<Timeline<K>, ScheduleErr<K>>
+where
+    K: Ord + Hash + Clone,
+    F: Fn(&K) -> usize,
+{
+    if workers == 0 {
+        return Err(ScheduleErr::NoWorkers);
+    }
+    if let Some(cycle) = graph.find_cycle() {
+        return Err(ScheduleErr::Cycle(cycle.0));
+    }
+
+    let mut waiting_on: HashMap<&K, usize> = graph
+        .nodes()
+        .map(|node| (node, graph.parents(node).count()))
+        .collect();
+    let mut ready: BTreeSet<&K> = waiting_on
+        .iter()
+        .filter(|&(_, &n)| n == 0)
+        .map(|(&node, _)| node)
+        .collect();
+    let mut free: BTreeSet<usize> = (0..workers).collect();
+    // (end, worker, task), earliest end first
+    let mut running: BinaryHeap<Reverse<(usize, usize, &K)>> = BinaryHeap::new();
+    let mut slots = Vec::with_capacity(graph.len());
+    let mut now = 0;
+
+    loop {
+        while let (Some(&task), Some(&worker)) = (ready.iter().next(), free.iter().next()) {
+            ready.remove(task);
+            free.remove(&worker);
+            let end = now + duration(task);
+            running.push(Reverse((end, worker, task)));
+            slots.push(Slot {
+                worker,
+                task: task.clone(),
+                start: now,
+                end,
+            });
+        }
+
+        // every step finishing at the same time frees its children before
+        // anything new is picked up
+        now = match running.peek() {
+            Some(&Reverse((end, _, _))) => end,
+            None => break,
+        };
+        while let Some(&Reverse((end, worker, task))) = running.peek() {
+            if end != now {
+                break;
+            }
+            running.pop();
+            free.insert(worker);
+            for (child, _) in graph.children(task) {
+                let waiting = waiting_on.get_mut(child).unwrap();
+                *waiting -= 1;
+                if *waiting == 0 {
+                    ready.insert(child);
+                }
+            }
+        }
+    }
+
+    slots.sort_by_key(|slot| (slot.start, slot.worker));
+    Ok(Timeline(slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_rules, step_cost, step_graph, StepGraph};
+
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+
+    fn graph(rules: &str) -> StepGraph {
+        step_graph(&parse_rules(rules))
+    }
+
+    #[test]
+    fn test_example() {
+        let timeline = schedule(&graph(EXAMPLE), 2, |s: &String| step_cost(s)).unwrap();
+        assert_eq!(timeline.total_time(), 15);
+        let order: String = timeline.0.iter().map(|s| s.task.as_str()).collect();
+        assert_eq!(order, "CAFBDE");
+        assert_eq!(
+            timeline.0[2],
+            Slot {
+                worker: 1,
+                task: "F".to_string(),
+                start: 3,
+                end: 9
+            }
+        );
+    }
+
+    #[test]
+    fn test_single_worker() {
+        let timeline = schedule(&graph(EXAMPLE), 1, |s: &String| step_cost(s)).unwrap();
+        assert_eq!(timeline.total_time(), (1..=6).sum());
+        let order: String = timeline.0.iter().map(|s| s.task.as_str()).collect();
+        assert_eq!(order, graph(EXAMPLE).toposort().unwrap().concat());
+    }
+
+    #[test]
+    fn test_csv() {
+        let timeline = schedule(&graph(EXAMPLE), 2, |s: &String| step_cost(s)).unwrap();
+        let csv = timeline.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("worker,task,start,end"));
+        assert_eq!(lines.next(), Some("0,C,0,3"));
+        assert_eq!(lines.last(), Some("0,E,10,15"));
+    }
+
+    #[test]
+    fn test_named_steps() {
+        let rules = "Step Shop must be finished before step Cook can begin.
+Step Prep must be finished before step Cook can begin.
+Step Cook must be finished before step Serve can begin.";
+        let timeline = schedule(&graph(rules), 2, |s: &String| s.len()).unwrap();
+        assert_eq!(timeline.total_time(), 4 + 4 + 5);
+        let csv = timeline.to_csv();
+        assert_eq!(csv.lines().nth(1), Some("0,Prep,0,4"));
+        assert_eq!(csv.lines().last(), Some("0,Serve,8,13"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            schedule(&graph(EXAMPLE), 0, |s: &String| step_cost(s)).unwrap_err(),
+            ScheduleErr::NoWorkers
+        );
+
+        let cyclic = format!(
+            "{}\nStep E must be finished before step A can begin.",
+            EXAMPLE
+        );
+        let err = schedule(&graph(&cyclic), 2, |s: &String| step_cost(s)).unwrap_err();
+        assert_eq!(
+            err,
+            ScheduleErr::Cycle(
+                vec!["A", "B", "E", "A"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            )
+        );
+        assert_eq!(
+            err.to_string(),
+            "steps depend on each other: A -> B -> E -> A"
+        );
+    }
+}
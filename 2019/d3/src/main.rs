@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::Path;
 
+mod segments;
+
+use segments::{Crossing, Segment};
+
 type Id = usize;
 type Len = usize;
 type Pos = (i64, i64);
@@ -53,106 +56,50 @@ where
         .collect()
 }
 
-fn part1() {
-    let wires = parse_wires("input.txt");
-
-    let mut counts: HashMap<Pos, HashSet<Id>> = HashMap::new();
-
-    for (wire_id, path) in &wires {
-        let mut pos = (0, 0);
-        for (dir, len) in path {
-            let (dx, dy): Pos = match dir {
-                Dir::L => (-1, 0),
-                Dir::R => (1, 0),
-                Dir::U => (0, 1),
-                Dir::D => (0, -1),
-            };
-
-            for _ in 0..*len {
-                pos = (pos.0 + dx, pos.1 + dy);
-                counts
-                    .entry(pos)
-                    .or_insert_with(|| HashSet::with_capacity(2))
-                    .insert(*wire_id);
-            }
-        }
-    }
+fn read_segments(args: &[String]) -> Vec<Vec<Segment>> {
+    parse_wires(args.get(2).map_or("input.txt", String::as_str))
+        .iter()
+        .map(|(_, path)| segments::segments(path))
+        .collect()
+}
 
-    let min_manhattan_distance_of_crossing = counts
+fn part1(args: &[String]) {
+    let min_manhattan_distance_of_crossing = segments::crossings(&read_segments(args))
         .iter()
-        .filter_map(|(pos, c)| {
-            if c.len() == wires.len() {
-                Some(pos)
-            } else {
-                None
-            }
-        })
-        .map(|(x, y)| x.abs() + y.abs())
+        .map(Crossing::distance)
         .min()
         .unwrap();
 
     println!("{}", min_manhattan_distance_of_crossing);
 }
 
-fn part2() {
-    let wires = parse_wires("input.txt");
-
-    let mut counts: HashMap<Pos, HashSet<Id>> = HashMap::new();
-    let mut visited_at: HashMap<Pos, HashMap<Id, usize>> = HashMap::new();
-
-    for (wire_id, path) in &wires {
-        let mut pos = (0, 0);
-        let mut step = 1; // this is 1 because (0, 0) counts
-
-        for (dir, len) in path {
-            let (dx, dy): Pos = match dir {
-                Dir::L => (-1, 0),
-                Dir::R => (1, 0),
-                Dir::U => (0, 1),
-                Dir::D => (0, -1),
-            };
-
-            for _ in 0..*len {
-                pos = (pos.0 + dx, pos.1 + dy);
-                let inserted = counts
-                    .entry(pos)
-                    .or_insert_with(|| HashSet::with_capacity(2))
-                    .insert(*wire_id);
-
-                if inserted {
-                    visited_at
-                        .entry(pos)
-                        .or_insert_with(|| HashMap::with_capacity(2))
-                        .insert(*wire_id, step);
-                }
-
-                step += 1;
-            }
-        }
-    }
-
-    let min_signal_delay: usize = counts
+fn part2(args: &[String]) {
+    let min_signal_delay = segments::crossings(&read_segments(args))
         .iter()
-        .filter_map(|(pos, c)| {
-            if c.len() == wires.len() {
-                Some(pos)
-            } else {
-                None
-            }
-        })
-        .map(|pos| visited_at.get(pos).unwrap().values().sum())
+        .map(Crossing::delay)
         .min()
         .unwrap();
 
     println!("{}", min_signal_delay);
 }
 
+fn self_crossings(args: &[String]) {
+    for (id, wire) in read_segments(args).iter().enumerate() {
+        let crossings = segments::self_crossings(wire);
+        println!("wire {}: crosses itself {} times", id, crossings.len());
+        for ((x, y), steps) in crossings {
+            println!("  ({}, {}) at steps {:?}", x, y, steps);
+        }
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     match &args[1][..] {
-        "1" => part1(),
-        "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "1" => part1(&args),
+        "2" => part2(&args),
+        "self" => self_crossings(&args),
+        _ => println!("Must pass either '1', '2' or 'self', and optionally an input file."),
     }
 }
 
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{Dir, Len, Pos};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    start: Pos,
+    end: Pos,
+    // steps taken by the wire to get to `start`
+    steps: usize,
+}
+
+impl Segment {
+    fn steps_at(&self, (x, y): Pos) -> usize {
+        self.steps
+            + (x - self.start.0).unsigned_abs() as usize
+            + (y - self.start.1).unsigned_abs() as usize
+    }
+
+    fn contains(&self, (x, y): Pos) -> bool {
+        let between = |v: i64, a: i64, b: i64| a.min(b) <= v && v <= a.max(b);
+        between(x, self.start.0, self.end.0) && between(y, self.start.1, self.end.1)
+    }
+
+    // Every point on both segments. Perpendicular segments share at most one
+    // point, but overlapping parallel ones share a whole stretch.
+    fn shared_points(&self, other: &Segment) -> impl Iterator<Item = Pos> {
+        let range = |a0: i64, a1: i64, b0: i64, b1: i64| {
+            a0.min(a1).max(b0.min(b1))..=a0.max(a1).min(b0.max(b1))
+        };
+        let xs = range(self.start.0, self.end.0, other.start.0, other.end.0);
+        let ys = range(self.start.1, self.end.1, other.start.1, other.end.1);
+        xs.flat_map(move |x| ys.clone().map(move |y| (x, y)))
+    }
+}
+
+pub fn segments(path: &[(Dir, Len)]) -> Vec<Segment> {
+    let mut pos = (0, 0);
+    let mut steps = 0;
+    path.iter()
+        .map(|&(dir, len)| {
+            let (dx, dy): Pos = match dir {
+                Dir::L => (-1, 0),
+                Dir::R => (1, 0),
+                Dir::U => (0, 1),
+                Dir::D => (0, -1),
+            };
+            let start = pos;
+            pos = (pos.0 + dx * len as i64, pos.1 + dy * len as i64);
+            let segment = Segment {
+                start,
+                end: pos,
+                steps,
+            };
+            steps += len;
+            segment
+        })
+        .collect()
+}
+
+// A point crossed by every wire, with the steps each wire takes to first
+// get there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crossing {
+    pub pos: Pos,
+    pub steps: Vec<usize>,
+}
+
+impl Crossing {
+    pub fn distance(&self) -> i64 {
+        self.pos.0.abs() + self.pos.1.abs()
+    }
+
+    pub fn delay(&self) -> usize {
+        self.steps.iter().sum()
+    }
+}
+
+fn first_visit(wire: &[Segment], pos: Pos) -> Option<usize> {
+    wire.iter()
+        .filter(|s| s.contains(pos))
+        .map(|s| s.steps_at(pos))
+        .min()
+}
+
+// Points where all wires meet, the central port excepted, sorted by position.
+// Candidates come from intersecting the first two wires segment by segment,
+// and are then checked against the others.
+pub fn crossings(wires: &[Vec<Segment>]) -> Vec<Crossing> {
+    let (first, second) = match wires {
+        [first, second, ..] => (first, second),
+        _ => return Vec::new(),
+    };
+
+    let mut candidates: BTreeMap<Pos, Vec<usize>> = BTreeMap::new();
+    for a in first {
+        for b in second {
+            for pos in a.shared_points(b).filter(|&pos| pos != (0, 0)) {
+                let steps = candidates.entry(pos).or_insert_with(|| vec![usize::MAX; 2]);
+                steps[0] = steps[0].min(a.steps_at(pos));
+                steps[1] = steps[1].min(b.steps_at(pos));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(pos, mut steps)| {
+            for wire in &wires[2..] {
+                steps.push(first_visit(wire, pos)?);
+            }
+            Some(Crossing { pos, steps })
+        })
+        .collect()
+}
+
+// Points a wire goes through more than once, with the steps of every visit,
+// sorted by position.
+pub fn self_crossings(wire: &[Segment]) -> Vec<(Pos, Vec<usize>)> {
+    let mut visits: HashMap<Pos, BTreeSet<usize>> = HashMap::new();
+    for (i, a) in wire.iter().enumerate() {
+        for b in &wire[i + 1..] {
+            for pos in a.shared_points(b) {
+                let (sa, sb) = (a.steps_at(pos), b.steps_at(pos));
+                // consecutive segments always share their joint
+                if sa != sb {
+                    visits.entry(pos).or_default().extend([sa, sb]);
+                }
+            }
+        }
+    }
+
+    let mut crossings: Vec<(Pos, Vec<usize>)> = visits
+        .into_iter()
+        .map(|(pos, steps)| (pos, steps.into_iter().collect()))
+        .collect();
+    crossings.sort();
+    crossings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_wire;
+
+    fn wires(raw: &[&str]) -> Vec<Vec<Segment>> {
+        raw.iter()
+            .enumerate()
+            .map(|(i, s)| segments(&parse_wire(i, s).1))
+            .collect()
+    }
+
+    fn closest(raw: &[&str]) -> (Option<i64>, Option<usize>) {
+        let crossings = crossings(&wires(raw));
+        (
+            crossings.iter().map(Crossing::distance).min(),
+            crossings.iter().map(Crossing::delay).min(),
+        )
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            closest(&["R8,U5,L5,D3", "U7,R6,D4,L4"]),
+            (Some(6), Some(30))
+        );
+        assert_eq!(
+            closest(&[
+                "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                "U62,R66,U55,R34,D71,R55,D58,R83"
+            ]),
+            (Some(159), Some(610))
+        );
+        assert_eq!(
+            closest(&[
+                "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"
+            ]),
+            (Some(135), Some(410))
+        );
+    }
+
+    #[test]
+    fn test_three_wires() {
+        // only (6, 5) is on all three
+        let raw = ["R8,U5,L5,D3", "U7,R6,D4,L4", "U5,R10"];
+        let crossings = crossings(&wires(&raw));
+        assert_eq!(
+            crossings,
+            vec![Crossing {
+                pos: (6, 5),
+                steps: vec![15, 15, 11]
+            }]
+        );
+        assert_eq!(closest(&["R8"]), (None, None));
+    }
+
+    #[test]
+    fn test_overlapping_wires() {
+        // running along each other from (2, 0) to (4, 0)
+        let crossings = crossings(&wires(&["R4", "U1,R2,D1,R5"]));
+        let points: Vec<Pos> = crossings.iter().map(|c| c.pos).collect();
+        assert_eq!(points, vec![(2, 0), (3, 0), (4, 0)]);
+        assert_eq!(crossings[0].steps, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_self_crossings() {
+        let wire = &wires(&["R4,U2,L2,D4"])[0];
+        assert_eq!(self_crossings(wire), vec![((2, 0), vec![2, 10])]);
+
+        // doubling back over itself
+        let wire = &wires(&["R3,L1"])[0];
+        assert_eq!(self_crossings(wire), vec![((2, 0), vec![2, 4])]);
+        assert!(self_crossings(&wires(&["R3,U1"])[0]).is_empty());
+    }
+}
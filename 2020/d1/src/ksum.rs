@@ -0,0 +1,151 @@
+// Every combination of `k` distinct entries of `values` adding up to
+// `target`, as sorted lists of indices into `values`. Equal values at
+// different indices are different entries.
+pub fn k_sum(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+    let mut sorted: Vec<(i64, usize)> = values.iter().copied().zip(0..).collect();
+    sorted.sort_unstable();
+
+    let mut found = Vec::new();
+    search(&sorted, k, target, &mut Vec::with_capacity(k), &mut found);
+    for combination in found.iter_mut() {
+        combination.sort_unstable();
+    }
+    found.sort();
+    found
+}
+
+fn search(
+    sorted: &[(i64, usize)],
+    k: usize,
+    target: i64,
+    chosen: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    if sorted.len() < k {
+        return;
+    }
+    match k {
+        0 if target == 0 => found.push(chosen.clone()),
+        0 => (),
+        1 => {
+            for &(_, i) in sorted.iter().filter(|&&(v, _)| v == target) {
+                let mut combination = chosen.clone();
+                combination.push(i);
+                found.push(combination);
+            }
+        }
+        2 => two_sum(sorted, target, chosen, found),
+        _ => {
+            for p in 0..=sorted.len() - k {
+                // the k smallest values left are already too much
+                if sorted[p..p + k].iter().map(|&(v, _)| v).sum::<i64>() > target {
+                    break;
+                }
+                chosen.push(sorted[p].1);
+                search(&sorted[p + 1..], k - 1, target - sorted[p].0, chosen, found);
+                chosen.pop();
+            }
+        }
+    }
+}
+
+// Two pointers closing in from both ends, jumping over runs of equal values
+// and pairing every entry of one run with every entry of the other.
+fn two_sum(sorted: &[(i64, usize)], target: i64, chosen: &[usize], found: &mut Vec<Vec<usize>>) {
+    let mut push = |a: usize, b: usize| {
+        let mut combination = chosen.to_vec();
+        combination.extend([sorted[a].1, sorted[b].1]);
+        found.push(combination);
+    };
+
+    let (mut lo, mut hi) = (0, sorted.len() - 1);
+    while lo < hi {
+        let (low, high) = (sorted[lo].0, sorted[hi].0);
+        if low + high < target {
+            lo += 1;
+        } else if low + high > target {
+            hi -= 1;
+        } else if low == high {
+            for a in lo..hi {
+                for b in a + 1..=hi {
+                    push(a, b);
+                }
+            }
+            break;
+        } else {
+            let lo_end = lo + sorted[lo..].iter().take_while(|&&(v, _)| v == low).count();
+            let hi_start = hi + 1
+                - sorted[..=hi]
+                    .iter()
+                    .rev()
+                    .take_while(|&&(v, _)| v == high)
+                    .count();
+            for a in lo..lo_end {
+                for b in hi_start..=hi {
+                    push(a, b);
+                }
+            }
+            lo = lo_end;
+            hi = hi_start - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    // every k-subset of indices, checked one by one
+    fn brute_force(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+        let mut found = Vec::new();
+        for mask in 0u32..1 << values.len() {
+            let indices: Vec<usize> = (0..values.len()).filter(|&i| mask & 1 << i != 0).collect();
+            if indices.len() == k && indices.iter().map(|&i| values[i]).sum::<i64>() == target {
+                found.push(indices);
+            }
+        }
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(k_sum(&EXAMPLE, 2, 2020), vec![vec![0, 3]]);
+        assert_eq!(k_sum(&EXAMPLE, 3, 2020), vec![vec![1, 2, 4]]);
+        assert!(k_sum(&EXAMPLE, 4, 2020).is_empty());
+    }
+
+    #[test]
+    fn test_duplicates() {
+        // 1010 only pairs with itself if it appears twice
+        assert!(k_sum(&[1010, 5], 2, 2020).is_empty());
+        assert_eq!(k_sum(&[1010, 5, 1010], 2, 2020), vec![vec![0, 2]]);
+        assert_eq!(
+            k_sum(&[1, 1, 1], 2, 2),
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+        );
+        assert_eq!(
+            k_sum(&[3, 1, 3, 1], 2, 4),
+            vec![vec![0, 1], vec![0, 3], vec![1, 2], vec![2, 3]]
+        );
+    }
+
+    #[test]
+    fn test_small_k() {
+        assert_eq!(k_sum(&[1, 2, 1], 1, 1), vec![vec![0], vec![2]]);
+        assert_eq!(k_sum(&[1, 2], 0, 0), vec![Vec::<usize>::new()]);
+        assert!(k_sum(&[1, 2], 3, 3).is_empty());
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let values = [4, -2, 7, 0, 3, 3, -5, 8, 1, 4, 2, -1];
+        for k in 0..=5 {
+            for target in -8..=20 {
+                assert_eq!(k_sum(&values, k, target), brute_force(&values, k, target));
+            }
+        }
+    }
+}
@@ -2,70 +2,42 @@ use std::env;
 use std::fs;
 use std::str::FromStr;
 
+mod ksum;
 
-fn part1() {
-    let mut result: Option<u64> = None;
+const TARGET: i64 = 2020;
 
-    let mut expenses: Vec<u64> = fs::read_to_string("input.txt")
+fn read_expenses() -> Vec<i64> {
+    fs::read_to_string("input.txt")
         .unwrap()
         .lines()
-        .map(|line| u64::from_str(line).unwrap())
-        .collect();
-
-    expenses.sort();
-
-    'outer: for (i, val1) in expenses.iter().enumerate() {
-        for val2 in expenses[i+1..].iter() {
-            if val1 + val2 == 2020 {
-                result = Some(val1 * val2);
-                break 'outer;
+        .map(|line| i64::from_str(line).unwrap())
+        .collect()
+}
 
-            } else if val1 + val2 > 2020 {
-                break
-            }
-        }
+// Product of the first `k` entries adding up to `target`.
+fn solve(k: usize, target: i64) {
+    let expenses = read_expenses();
+    match ksum::k_sum(&expenses, k, target).first() {
+        Some(indices) => println!("{}", indices.iter().map(|&i| expenses[i]).product::<i64>()),
+        None => println!("No {} entries add up to {}.", k, target),
     }
-
-    println!("{}", result.unwrap());
 }
 
-
-fn part2() {
-    let mut result: Option<u64> = None;
-
-    let mut expenses: Vec<u64> = fs::read_to_string("input.txt")
-        .unwrap()
-        .lines()
-        .map(|line| u64::from_str(line).unwrap())
-        .collect();
-
-    expenses.sort();
-
-    'outer: for (i, val1) in expenses.iter().enumerate() {
-        for val2 in expenses[i+1..].iter() {
-            for val3 in expenses[i+2..].iter() {
-                if val1 + val2 + val3 == 2020 {
-                    result = Some(val1 * val2 * val3);
-                    break 'outer;
-
-                } else if val1 + val2 + val3 > 2020 {
-                    break
-                }
-            }
-        }
+fn list_all(k: usize, target: i64) {
+    let expenses = read_expenses();
+    for indices in ksum::k_sum(&expenses, k, target) {
+        let values: Vec<String> = indices.iter().map(|&i| expenses[i].to_string()).collect();
+        println!("{:?}: {} = {}", indices, values.join(" + "), target);
     }
-
-    println!("{}", result.unwrap());
 }
 
-
-
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let target = |i: usize| args.get(i).map_or(TARGET, |t| t.parse().unwrap());
     match &args[1][..] {
-        "1" => part1(),
-        "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "1" => solve(2, target(2)),
+        "2" => solve(3, target(2)),
+        "all" if args.len() > 2 => list_all(args[2].parse().unwrap(), target(3)),
+        _ => println!("Must pass either '1' or '2', or 'all <k>', then optionally a target."),
     }
 }
-
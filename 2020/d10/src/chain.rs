@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+
+use crate::Adapter;
+
+// Two joltages in the chain that no allowed step connects.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Gap {
+    pub from: Adapter,
+    pub to: Adapter,
+}
+
+// There are more arrangements than fit in a u128.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TooMany;
+
+// The outlet, every adapter by joltage, and the device, which is rated the
+// largest step above the highest adapter.
+pub struct Chain {
+    joltages: Vec<Adapter>,
+    steps: Vec<Adapter>,
+    // number of ways to get from each joltage to the device, if it fits
+    to_device: Vec<Option<u128>>,
+}
+
+impl Chain {
+    pub fn new(adapters: &[Adapter], steps: &[Adapter]) -> Self {
+        let mut steps = steps.to_vec();
+        steps.sort_unstable();
+        steps.dedup();
+
+        let mut joltages = Vec::with_capacity(adapters.len() + 2);
+        joltages.push(0);
+        joltages.extend(adapters);
+        joltages.sort_unstable();
+        let highest = *joltages.last().unwrap();
+        joltages.push(highest + steps.last().copied().unwrap_or(0));
+
+        let mut chain = Chain {
+            joltages,
+            steps,
+            to_device: Vec::new(),
+        };
+        chain.to_device = chain.count_to_device();
+        chain
+    }
+
+    fn allowed(&self, from: usize, to: usize) -> bool {
+        self.steps
+            .binary_search(&(self.joltages[to] - self.joltages[from]))
+            .is_ok()
+    }
+
+    // Indices of the joltages one allowed step above joltage `i`. Only the
+    // few joltages within the largest step are looked at.
+    fn successors(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let max_step = self.steps.last().copied().unwrap_or(0);
+        (i + 1..self.joltages.len())
+            .take_while(move |&j| self.joltages[j] - self.joltages[i] <= max_step)
+            .filter(move |&j| self.allowed(i, j))
+    }
+
+    // A count that overflows also makes every count depending on it
+    // overflow.
+    fn count_to_device(&self) -> Vec<Option<u128>> {
+        let n = self.joltages.len();
+        let mut counts = vec![None; n];
+        counts[n - 1] = Some(1);
+        for i in (0..n - 1).rev() {
+            counts[i] = self
+                .successors(i)
+                .try_fold(0u128, |acc, j| acc.checked_add(counts[j]?));
+        }
+        counts
+    }
+
+    // How many times each step is taken when using every adapter.
+    pub fn histogram(&self) -> Result<BTreeMap<Adapter, usize>, Gap> {
+        let mut histogram = BTreeMap::new();
+        for i in 1..self.joltages.len() {
+            if !self.allowed(i - 1, i) {
+                return Err(Gap {
+                    from: self.joltages[i - 1],
+                    to: self.joltages[i],
+                });
+            }
+            *histogram
+                .entry(self.joltages[i] - self.joltages[i - 1])
+                .or_default() += 1;
+        }
+        Ok(histogram)
+    }
+
+    // Number of distinct ways to get from the outlet to the device.
+    pub fn arrangements(&self) -> Result<u128, TooMany> {
+        self.to_device[0].ok_or(TooMany)
+    }
+
+    // The `k`th arrangement, in lexicographic order of the joltages used,
+    // from the outlet to the device included, if there are more than `k`.
+    pub fn nth(&self, mut k: u128) -> Result<Option<Vec<Adapter>>, TooMany> {
+        if k >= self.arrangements()? {
+            return Ok(None);
+        }
+        let mut i = 0;
+        let mut arrangement = vec![self.joltages[0]];
+        while i < self.joltages.len() - 1 {
+            for j in self.successors(i) {
+                // counted from the outlet, so no count after it overflows
+                let count = self.to_device[j].unwrap();
+                if k < count {
+                    i = j;
+                    break;
+                }
+                k -= count;
+            }
+            arrangement.push(self.joltages[i]);
+        }
+        Ok(Some(arrangement))
+    }
+
+    // An arrangement picked uniformly, the same one for the same `seed`.
+    pub fn sample(&self, seed: u64) -> Result<Option<Vec<Adapter>>, TooMany> {
+        let count = self.arrangements()?;
+        if count == 0 {
+            return Ok(None);
+        }
+        self.nth(uniform(count, splitmix(seed)))
+    }
+}
+
+// splitmix64, two outputs at a time.
+fn splitmix(seed: u64) -> impl FnMut() -> u128 {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    move || (next() as u128) << 64 | next() as u128
+}
+
+// Largest draw kept when picking below `count`: draws past the last whole
+// multiple of `count` would favour low ranks.
+fn draw_limit(count: u128) -> u128 {
+    u128::MAX - (u128::MAX % count + 1) % count
+}
+
+// A rank below `count`, drawing until a draw is under the limit.
+fn uniform(count: u128, mut draw: impl FnMut() -> u128) -> u128 {
+    let limit = draw_limit(count);
+    loop {
+        let draw = draw();
+        if draw <= limit {
+            return draw % count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: [Adapter; 11] = [16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+    const LARGE: [Adapter; 31] = [
+        28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
+        17, 7, 9, 4, 2, 34, 10, 3,
+    ];
+
+    #[test]
+    fn test_histogram() {
+        let chain = Chain::new(&SMALL, &[1, 2, 3]);
+        assert_eq!(chain.histogram(), Ok(BTreeMap::from([(1, 7), (3, 5)])));
+        let chain = Chain::new(&LARGE, &[1, 2, 3]);
+        assert_eq!(chain.histogram(), Ok(BTreeMap::from([(1, 22), (3, 10)])));
+        // 1 to 4 is a step of 3
+        let chain = Chain::new(&SMALL, &[1, 2]);
+        assert_eq!(chain.histogram(), Err(Gap { from: 1, to: 4 }));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        assert_eq!(Chain::new(&SMALL, &[1, 2, 3]).arrangements(), Ok(8));
+        assert_eq!(Chain::new(&LARGE, &[1, 2, 3]).arrangements(), Ok(19208));
+        // without steps of 2, only 5 and 6 can be skipped, together
+        assert_eq!(Chain::new(&SMALL, &[1, 3]).arrangements(), Ok(2));
+        assert_eq!(Chain::new(&SMALL, &[2]).arrangements(), Ok(0));
+    }
+
+    #[test]
+    fn test_too_many() {
+        // with steps of 1 and 2, the counts are Fibonacci numbers, and
+        // F(187) is the first one past u128::MAX
+        let adapters: Vec<Adapter> = (1..=185).collect();
+        let chain = Chain::new(&adapters, &[1, 2]);
+        assert!(chain.arrangements().is_ok());
+        let adapters: Vec<Adapter> = (1..=186).collect();
+        let chain = Chain::new(&adapters, &[1, 2]);
+        assert_eq!(chain.arrangements(), Err(TooMany));
+        assert_eq!(chain.nth(0), Err(TooMany));
+    }
+
+    #[test]
+    fn test_nth() {
+        let chain = Chain::new(&SMALL, &[1, 2, 3]);
+        let all: Vec<Vec<Adapter>> = (0..chain.arrangements().unwrap())
+            .map(|k| chain.nth(k).unwrap().unwrap())
+            .collect();
+        assert_eq!(all[0], vec![0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22]);
+        assert_eq!(all[7], vec![0, 1, 4, 7, 10, 12, 15, 16, 19, 22]);
+        let mut sorted = all.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, all);
+        assert_eq!(chain.nth(8), Ok(None));
+    }
+
+    #[test]
+    fn test_sample() {
+        let chain = Chain::new(&LARGE, &[1, 2, 3]);
+        let sample = chain.sample(7).unwrap().unwrap();
+        assert_eq!(chain.sample(7), Ok(Some(sample.clone())));
+        assert!(
+            (0..chain.arrangements().unwrap()).any(|k| chain.nth(k) == Ok(Some(sample.clone())))
+        );
+
+        // only one way through, whatever the seed
+        let chain = Chain::new(&[1, 2, 3], &[1]);
+        assert_eq!(chain.arrangements(), Ok(1));
+        for seed in 0..20 {
+            assert_eq!(chain.sample(seed), Ok(Some(vec![0, 1, 2, 3, 4])));
+        }
+
+        assert_eq!(Chain::new(&SMALL, &[2]).sample(0), Ok(None));
+        let adapters: Vec<Adapter> = (1..=186).collect();
+        assert_eq!(Chain::new(&adapters, &[1, 2]).sample(0), Err(TooMany));
+    }
+
+    #[test]
+    fn test_uniform() {
+        // powers of two divide 2^128, so nothing is thrown away
+        assert_eq!(draw_limit(1), u128::MAX);
+        assert_eq!(draw_limit(2), u128::MAX);
+        assert_eq!(draw_limit(1 << 100), u128::MAX);
+        // 2^128 = 1 mod 3, so the very last draw would favour rank 0
+        assert_eq!(draw_limit(3), u128::MAX - 1);
+        assert_eq!(draw_limit(u128::MAX), u128::MAX - 1);
+
+        // the last draw is thrown away, and the next one kept
+        let mut draws = [u128::MAX, 5].into_iter();
+        assert_eq!(uniform(3, || draws.next().unwrap()), 2);
+        let mut draws = [u128::MAX].into_iter();
+        assert_eq!(uniform(1, || draws.next().unwrap()), 0);
+    }
+}
@@ -3,8 +3,11 @@ use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-use itertools::Itertools;
-use ndarray::{Array, Dim};
+mod chain;
+
+use chain::{Chain, TooMany};
+
+const STEPS: [Adapter; 3] = [1, 2, 3];
 
 type Adapter = u64;
 
@@ -16,68 +19,74 @@ where
         .unwrap()
         .lines()
         .map(|l| Adapter::from_str(l).unwrap())
-        .sorted()
         .collect()
 }
 
-fn part1() {
-    let adapters = parse_adapters("input.txt");
-
-    let counts = (0..1)
-        .chain(adapters.iter().copied())
-        .zip(adapters.iter().copied())
-        .map(|(x, y)| y - x)
-        .counts();
+// Allowed steps as a comma separated list, 1,2,3 by default.
+fn read_chain(args: &[String], i: usize) -> Chain {
+    let steps: Vec<Adapter> = args.get(i).map_or(STEPS.to_vec(), |s| {
+        s.split(',').map(|step| step.parse().unwrap()).collect()
+    });
+    Chain::new(&parse_adapters("input.txt"), &steps)
+}
 
-    let res = counts.get(&1).unwrap() * (1 + counts.get(&3).unwrap());
-    println!("{}", res);
+fn part1(args: &[String]) {
+    let histogram = read_chain(args, 2).histogram().unwrap();
+    let count = |step| histogram.get(&step).copied().unwrap_or(0);
+    println!("{}", count(1) * count(3));
 }
 
-fn build_adjancy_matrix(nodes: &Vec<Adapter>) -> Array<Adapter, Dim<[usize; 2]>> {
-    let n = nodes.len();
-    let mut m = Array::zeros((n, n));
-    let indexed: Vec<(usize, Adapter)> = nodes.iter().copied().enumerate().collect();
+fn part2(args: &[String]) {
+    match read_chain(args, 2).arrangements() {
+        Ok(count) => println!("{}", count),
+        Err(TooMany) => println!("More than {}", u128::MAX),
+    }
+}
 
-    for (i, from) in &indexed[..] {
-        for (j, to) in &indexed[i + 1..] {
-            if from + 3 < *to {
-                break;
+fn histogram(args: &[String]) {
+    match read_chain(args, 2).histogram() {
+        Ok(histogram) => {
+            for (step, count) in histogram {
+                println!("{}: {}", step, count);
             }
-
-            m[[*i, *j]] = 1;
         }
+        Err(gap) => println!("No allowed step from {} to {}", gap.from, gap.to),
     }
-    m[[n - 1, n - 1]] = 1;
-    m
 }
 
-fn part2() {
-    let adapters = parse_adapters("input.txt");
-
-    let nodes: Vec<Adapter> = {
-        let mut nodes = Vec::with_capacity(adapters.len() + 2);
-        nodes.extend((0..1).chain(adapters.iter().copied()));
-        nodes.push(nodes.last().unwrap() + 3);
-        nodes
+// The kth arrangement, or a random one with `random <seed>`.
+fn nth(args: &[String], random: bool) {
+    let chain = read_chain(args, 3);
+    let arrangement = if random {
+        chain.sample(args[2].parse().unwrap())
+    } else {
+        chain.nth(args[2].parse().unwrap())
     };
-
-    let mut m = build_adjancy_matrix(&nodes);
-
-    while {
-        let m2 = m.clone();
-        m = m.dot(&m);
-
-        m2 != m
-    } {}
-
-    println!("{}", m[[0, nodes.len() - 1]]);
+    match arrangement {
+        Ok(Some(arrangement)) => println!(
+            "{}",
+            arrangement
+                .iter()
+                .map(|j| j.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        Ok(None) => println!(
+            "There are only {} arrangements",
+            chain.arrangements().unwrap()
+        ),
+        Err(TooMany) => println!("Too many arrangements to rank them"),
+    }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     match &args[1][..] {
-        "1" => part1(),
-        "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "1" => part1(&args),
+        "2" => part2(&args),
+        "histogram" => histogram(&args),
+        "nth" => nth(&args, false),
+        "random" => nth(&args, true),
+        _ => println!("Must pass either '1', '2', 'histogram', 'nth <k>' or 'random <seed>', and optionally the allowed steps."),
     }
 }
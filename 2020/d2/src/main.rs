@@ -2,6 +2,10 @@ use std::env;
 use std::fs;
 use std::str::FromStr;
 
+mod policy;
+
+use policy::Policy;
+
 #[derive(Debug, Clone)]
 struct Line {
     lower: usize,
@@ -36,35 +40,51 @@ impl FromStr for Line {
     }
 }
 
-fn count_valid_lines<F>(f: F) -> usize
-where
-    F: FnMut(&Line) -> bool,
-{
-    fs::read_to_string("input.txt")
+// Every line of the input, with the parsed line if it could be parsed.
+fn parse_lines(path: &str) -> Vec<(String, Result<Line, ()>)> {
+    fs::read_to_string(path)
         .unwrap()
         .lines()
-        .filter_map(|l| Line::from_str(l).ok())
-        .filter(f)
-        .count()
+        .map(|l| (l.to_string(), Line::from_str(l)))
+        .collect()
 }
 
-fn is_valid_part1(line: &Line) -> bool {
-    let count = line.password.chars().filter(|&c| c == line.letter).count();
-    count >= line.lower && count <= line.upper
+fn read_policy(policy: &str) -> Policy {
+    policy.parse().unwrap_or_else(|e| panic!("{}", e))
 }
 
-fn is_valid_part2(line: &Line) -> bool {
-    let chars = line.password.chars().collect::<Vec<_>>();
-
-    (chars[line.lower - 1] == line.letter) ^ (chars[line.upper - 1] == line.letter)
+fn count_valid_lines(policy: &Policy) -> usize {
+    parse_lines("input.txt")
+        .iter()
+        .filter_map(|(_, line)| line.as_ref().ok())
+        .filter(|l| policy.check(l).is_ok())
+        .count()
 }
 
 fn part1() {
-    println!("{}", count_valid_lines(is_valid_part1));
+    println!("{}", count_valid_lines(&read_policy("count")));
 }
 
 fn part2() {
-    println!("{}", count_valid_lines(is_valid_part2));
+    println!("{}", count_valid_lines(&read_policy("positions")));
+}
+
+fn check(args: &[String]) {
+    println!("{}", count_valid_lines(&read_policy(&args[2])));
+}
+
+// The first clause each line fails, if any.
+fn report(args: &[String]) {
+    let policy = read_policy(&args[2]);
+    for (i, (raw, line)) in parse_lines("input.txt").iter().enumerate() {
+        match line {
+            Ok(line) => match policy.check(line) {
+                Ok(()) => println!("{}: {} ok", i + 1, line.password),
+                Err(clause) => println!("{}: {} fails {}", i + 1, line.password, clause),
+            },
+            Err(()) => println!("{}: cannot parse '{}'", i + 1, raw),
+        }
+    }
 }
 
 fn main() {
@@ -72,6 +92,8 @@ fn main() {
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "check" => check(&args),
+        "report" => report(&args),
+        _ => println!("Must pass either '1', '2', 'check <policy>' or 'report <policy>', with policies made of 'count', 'positions', 'forbid:<chars>' or 'regex:/<re>/' joined by '+' (a '/' in a regex is written '\\/')."),
     }
 }
@@ -0,0 +1,273 @@
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::Line;
+
+// One requirement on a password, using the numbers and letter of its line
+// where it needs them.
+#[derive(Debug, Clone)]
+pub enum Clause {
+    // the letter appears between the two numbers of times, both included
+    Count,
+    // the letter is at exactly one of the two 1-based positions
+    Positions,
+    // none of these characters appear
+    Forbid(String),
+    Matches(Regex),
+}
+
+impl Clause {
+    pub fn check(&self, line: &Line) -> bool {
+        match self {
+            Clause::Count => {
+                let count = line.password.chars().filter(|&c| c == line.letter).count();
+                (line.lower..=line.upper).contains(&count)
+            }
+            Clause::Positions => {
+                let at = |pos: usize| {
+                    pos.checked_sub(1)
+                        .and_then(|i| line.password.chars().nth(i))
+                        == Some(line.letter)
+                };
+                at(line.lower) ^ at(line.upper)
+            }
+            Clause::Forbid(chars) => !line.password.chars().any(|c| chars.contains(c)),
+            Clause::Matches(re) => re.is_match(&line.password),
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Clause::Count => write!(f, "count"),
+            Clause::Positions => write!(f, "positions"),
+            Clause::Forbid(chars) => write!(f, "forbid:{}", chars),
+            Clause::Matches(re) => write!(f, "regex:/{}/", re.as_str().replace('/', "\\/")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PolicyErr {
+    UnknownClause(String),
+    BadRegex(String),
+    // a regex not written between two '/'
+    Undelimited(String),
+    Empty,
+}
+
+impl fmt::Display for PolicyErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyErr::UnknownClause(clause) => write!(f, "unknown clause '{}'", clause),
+            PolicyErr::BadRegex(re) => write!(f, "invalid regex '{}'", re),
+            PolicyErr::Undelimited(clause) => write!(f, "regex not between '/'s in '{}'", clause),
+            PolicyErr::Empty => write!(f, "no clauses"),
+        }
+    }
+}
+
+impl FromStr for Clause {
+    type Err = PolicyErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "count" => Ok(Clause::Count),
+            None if s == "positions" => Ok(Clause::Positions),
+            Some(("forbid", chars)) => Ok(Clause::Forbid(chars.to_string())),
+            Some(("regex", re)) => {
+                let re = re
+                    .strip_prefix('/')
+                    .and_then(|re| re.strip_suffix('/'))
+                    .filter(|re| closing_slash(re).is_none())
+                    .ok_or_else(|| PolicyErr::Undelimited(s.to_string()))?;
+                let re = unescape_slashes(re);
+                Regex::new(&re)
+                    .map(Clause::Matches)
+                    .map_err(|_| PolicyErr::BadRegex(re))
+            }
+            _ => Err(PolicyErr::UnknownClause(s.to_string())),
+        }
+    }
+}
+
+// Position of the '/' ending a regex, skipping the ones written '\/'.
+fn closing_slash(re: &str) -> Option<usize> {
+    let mut chars = re.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '/' => return Some(i),
+            _ => (),
+        }
+    }
+    None
+}
+
+// Turns every '\/' back into the '/' it stands for.
+fn unescape_slashes(re: &str) -> String {
+    let mut unescaped = String::with_capacity(re.len());
+    let mut chars = re.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('/')) => {
+                unescaped.push('/');
+                chars.next();
+            }
+            ('\\', Some(escaped)) => {
+                unescaped.push(c);
+                unescaped.push(escaped);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+// Clauses that must all hold, written joined by '+', as in
+// `count+forbid:xyz+regex:/^[a-z]+$/`. A regex goes between two '/', so
+// that it can hold anything, writing '/' itself as '\/'.
+#[derive(Debug, Clone)]
+pub struct Policy(Vec<Clause>);
+
+impl FromStr for Policy {
+    type Err = PolicyErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(PolicyErr::Empty);
+        }
+        let mut clauses = Vec::new();
+        let mut rest = s;
+        loop {
+            let end = match rest.strip_prefix("regex:/") {
+                Some(re) => match closing_slash(re) {
+                    Some(i) => "regex:/".len() + i + 1,
+                    None => return Err(PolicyErr::Undelimited(rest.to_string())),
+                },
+                None => rest.find('+').unwrap_or(rest.len()),
+            };
+            clauses.push(rest[..end].parse()?);
+            rest = match &rest[end..] {
+                "" => break,
+                after => after
+                    .strip_prefix('+')
+                    .ok_or_else(|| PolicyErr::UnknownClause(after.to_string()))?,
+            };
+        }
+        Ok(Policy(clauses))
+    }
+}
+
+impl Policy {
+    // The first clause the line fails.
+    pub fn check(&self, line: &Line) -> Result<(), &Clause> {
+        match self.0.iter().find(|clause| !clause.check(line)) {
+            Some(clause) => Err(clause),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(s: &str) -> Line {
+        Line::from_str(s).unwrap()
+    }
+
+    fn check(policy: &str, l: &str) -> Result<(), String> {
+        Policy::from_str(policy)
+            .unwrap()
+            .check(&line(l))
+            .map_err(|clause| clause.to_string())
+    }
+
+    #[test]
+    fn test_example() {
+        let lines = ["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"];
+        let valid = |policy: &str| lines.iter().filter(|l| check(policy, l).is_ok()).count();
+        assert_eq!(valid("count"), 2);
+        assert_eq!(valid("positions"), 1);
+        assert_eq!(valid("count+positions"), 1);
+    }
+
+    #[test]
+    fn test_clauses() {
+        assert_eq!(check("forbid:xyz", "1-3 a: abcde"), Ok(()));
+        assert_eq!(
+            check("count+forbid:cd", "1-3 a: abcde"),
+            Err("forbid:cd".to_string())
+        );
+        assert_eq!(check("regex:/^a.+e$/", "1-3 a: abcde"), Ok(()));
+        assert_eq!(
+            check("regex:/^z+$/+count", "1-3 z: zzzz"),
+            Err("count".to_string())
+        );
+        // positions past the end of the password never hold the letter
+        assert_eq!(check("positions", "2-9 c: cc"), Ok(()));
+        assert_eq!(
+            check("positions", "0-9 c: cc"),
+            Err("positions".to_string())
+        );
+    }
+
+    #[test]
+    fn test_regex_delimiters() {
+        // anything between the '/'s belongs to the regex
+        assert_eq!(check("regex:/a+count/", "1-1 a: aacount"), Ok(()));
+        assert_eq!(
+            check("regex:/a+count/", "1-1 a: count"),
+            Err("regex:/a+count/".to_string())
+        );
+        assert_eq!(check("regex:/x+forbid:y/", "1-1 x: xforbid:y"), Ok(()));
+        assert_eq!(
+            check("count+regex:/x+forbid:y/", "1-1 x: xforbid:z"),
+            Err("regex:/x+forbid:y/".to_string())
+        );
+        assert_eq!(check("regex:/^a\\/b$/+count", "1-1 a: a/b"), Ok(()));
+        assert_eq!(
+            check("regex:/^a\\/b$/", "1-1 a: ab"),
+            Err("regex:/^a\\/b$/".to_string())
+        );
+        // other escapes stay as they are
+        assert_eq!(check("regex:/^\\d+$/", "1-1 1: 123"), Ok(()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Policy::from_str("count+length").unwrap_err(),
+            PolicyErr::UnknownClause("length".to_string())
+        );
+        // a typo after a forbidden set is not more forbidden characters
+        assert_eq!(
+            Policy::from_str("forbid:a+lenght").unwrap_err(),
+            PolicyErr::UnknownClause("lenght".to_string())
+        );
+        assert_eq!(
+            Policy::from_str("regex:/(/").unwrap_err(),
+            PolicyErr::BadRegex("(".to_string())
+        );
+        assert_eq!(
+            Policy::from_str("count+regex:/a+b").unwrap_err(),
+            PolicyErr::Undelimited("regex:/a+b".to_string())
+        );
+        assert_eq!(
+            Policy::from_str("regex:a+b").unwrap_err(),
+            PolicyErr::Undelimited("regex:a".to_string())
+        );
+        assert_eq!(
+            Policy::from_str("regex:/a/b+count").unwrap_err(),
+            PolicyErr::UnknownClause("b+count".to_string())
+        );
+        assert_eq!(Policy::from_str("").unwrap_err(), PolicyErr::Empty);
+    }
+}
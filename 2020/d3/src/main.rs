@@ -3,77 +3,57 @@ use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
-struct Map {
-    rows: Vec<Vec<bool>>,
-    ncols: usize,
-    nrows: usize,
-}
-
-impl FromStr for Map {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let rows: Vec<Vec<bool>> = s
-            .lines()
-            .map(|l| l.chars().map(|c| c == '#').collect())
-            .collect();
-        let nrows = rows.len();
-        let ncols = rows[0].len();
-
-        Ok(Map { rows, ncols, nrows })
-    }
-}
-
-impl Map {
-    fn from_file<P>(path: P) -> Self
-    where
-        P: AsRef<Path>,
-    {
-        fs::read_to_string(path)
-            .map(|s| Map::from_str(&s))
-            .unwrap()
-            .unwrap()
-    }
+mod terrain;
 
-    fn has_tree(&self, row: usize, col: usize) -> bool {
-        self.rows[row % self.nrows][col % self.ncols]
-    }
+use terrain::{Map, Slope, Wrap};
 
-    fn count_trees(&self, delta_row: usize, delta_col: usize) -> usize {
-        let mut count = 0;
-        let mut row = 0;
-        let mut col = 0;
+const SLOPES: [&str; 5] = ["1", "3", "5", "7", "1,2"];
 
-        while row < self.nrows {
-            if self.has_tree(row, col) {
-                count += 1;
-            }
-            row += delta_row;
-            col += delta_col;
-        }
+fn read_map<P>(path: P) -> Map
+where
+    P: AsRef<Path>,
+{
+    Map::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
 
-        count
-    }
+fn read_wrap(args: &[String], i: usize) -> Wrap {
+    args.get(i).map_or(Wrap::Horizontal, |s| s.parse().unwrap())
 }
 
 fn part1() {
-    let count = Map::from_file("input.txt").count_trees(1, 3);
+    let count = read_map("input.txt").count_trees(Slope::new(3, 1).unwrap(), Wrap::Horizontal);
     println!("{}", count);
 }
 
 fn part2() {
-    let map = Map::from_file("input.txt");
-
-    let deltas = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
-
-    let mut total = 1;
+    let slopes: Vec<Slope> = SLOPES.iter().map(|s| s.parse().unwrap()).collect();
+    println!(
+        "{}",
+        read_map("input.txt").product(&slopes, Wrap::Horizontal)
+    );
+}
 
-    for (delta_row, delta_col) in deltas {
-        let c = map.count_trees(delta_row, delta_col);
-        total *= c
+// Trees on each slope given as `right,down`, then their product.
+fn slopes(args: &[String]) {
+    let map = read_map("input.txt");
+    let wrap = read_wrap(args, 3);
+    let slopes: Vec<Slope> = args[2]
+        .split_whitespace()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    for &slope in &slopes {
+        println!("{}: {}", slope, map.count_trees(slope, wrap));
     }
+    println!("{}", map.product(&slopes, wrap));
+}
 
-    println!("{}", total);
+fn fewest(args: &[String]) {
+    let map = read_map("input.txt");
+    let (max_right, max_down) = (args[2].parse().unwrap(), args[3].parse().unwrap());
+    match map.fewest_trees(max_right, max_down, read_wrap(args, 4)) {
+        Some((slope, trees)) => println!("{}: {}", slope, trees),
+        None => println!("No slope to try"),
+    }
 }
 
 fn main() {
@@ -81,6 +61,8 @@ fn main() {
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "slopes" => slopes(&args),
+        "fewest" => fewest(&args),
+        _ => println!("Must pass either '1', '2', 'slopes <right,down ...> [wrap]' or 'fewest <max right> <max down> [wrap]'."),
     }
 }
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+// Columns moved right (or left, if negative) for every `down` rows, taken
+// as a single step: 1 right for 2 down only lands on every other row, and
+// 2 right for 2 down is not the same as 1 right for 1 down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slope {
+    right: i64,
+    down: u64,
+}
+
+impl Slope {
+    pub fn new(right: i64, down: u64) -> Option<Self> {
+        if down == 0 {
+            return None;
+        }
+        Some(Slope { right, down })
+    }
+}
+
+impl FromStr for Slope {
+    type Err = ();
+
+    // `right,down`, or just `right` to go down one row at a time.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s.split_once(',').unwrap_or((s, "1"));
+        let right = right.trim().parse().map_err(|_| ())?;
+        let down = down.trim().parse().map_err(|_| ())?;
+        Slope::new(right, down).ok_or(())
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "right {}, down {}", self.right, self.down)
+    }
+}
+
+// Which edges of the map repeat it. The ride stops when it goes over any
+// other edge, or when it gets back to a place it has already been.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    Horizontal,
+    Vertical,
+    Both,
+    Bounded,
+}
+
+impl FromStr for Wrap {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "horizontal" => Ok(Wrap::Horizontal),
+            "vertical" => Ok(Wrap::Vertical),
+            "both" => Ok(Wrap::Both),
+            "bounded" => Ok(Wrap::Bounded),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    rows: Vec<Vec<bool>>,
+    ncols: usize,
+    nrows: usize,
+}
+
+impl FromStr for Map {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<Vec<bool>> = s
+            .lines()
+            .map(|l| l.chars().map(|c| c == '#').collect())
+            .collect();
+        let nrows = rows.len();
+        let ncols = rows.first().ok_or(())?.len();
+        if ncols == 0 || rows.iter().any(|r| r.len() != ncols) {
+            return Err(());
+        }
+
+        Ok(Map { rows, ncols, nrows })
+    }
+}
+
+impl Map {
+    // Where (row, col) ends up on the map, if it is still on it.
+    fn locate(&self, row: i64, col: i64, wrap: Wrap) -> Option<(usize, usize)> {
+        let (wrap_rows, wrap_cols) = match wrap {
+            Wrap::Horizontal => (false, true),
+            Wrap::Vertical => (true, false),
+            Wrap::Both => (true, true),
+            Wrap::Bounded => (false, false),
+        };
+        let fit = |v: i64, n: usize, wraps: bool| {
+            if wraps {
+                Some(v.rem_euclid(n as i64) as usize)
+            } else if (0..n as i64).contains(&v) {
+                Some(v as usize)
+            } else {
+                None
+            }
+        };
+        Some((
+            fit(row, self.nrows, wrap_rows)?,
+            fit(col, self.ncols, wrap_cols)?,
+        ))
+    }
+
+    pub fn count_trees(&self, slope: Slope, wrap: Wrap) -> usize {
+        // only wrapping rows can bring the ride back to a row it went over
+        let mut seen = matches!(wrap, Wrap::Vertical | Wrap::Both).then(HashSet::new);
+        let mut count = 0;
+        let (mut row, mut col) = (0, 0);
+        while let Some(pos) = self.locate(row, col, wrap) {
+            if seen.as_mut().is_some_and(|seen| !seen.insert(pos)) {
+                break;
+            }
+            if self.rows[pos.0][pos.1] {
+                count += 1;
+            }
+            row += slope.down as i64;
+            col += slope.right;
+        }
+        count
+    }
+
+    pub fn product(&self, slopes: &[Slope], wrap: Wrap) -> usize {
+        slopes.iter().map(|&s| self.count_trees(s, wrap)).product()
+    }
+
+    // Slope hitting the fewest trees among those going at most `max_right`
+    // columns either way and `max_down` rows at a time. Ties go to the one
+    // found first, by rows then columns moved.
+    pub fn fewest_trees(
+        &self,
+        max_right: i64,
+        max_down: u64,
+        wrap: Wrap,
+    ) -> Option<(Slope, usize)> {
+        (1..=max_down)
+            .flat_map(|down| {
+                (-max_right..=max_right).filter_map(move |right| Slope::new(right, down))
+            })
+            .map(|slope| (slope, self.count_trees(slope, wrap)))
+            .min_by_key(|&(_, trees)| trees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    fn slope(s: &str) -> Slope {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let map = Map::from_str(EXAMPLE).unwrap();
+        assert_eq!(map.count_trees(slope("3"), Wrap::Horizontal), 7);
+        let slopes: Vec<Slope> = ["1", "3", "5", "7", "1,2"]
+            .iter()
+            .map(|s| slope(s))
+            .collect();
+        let trees: Vec<usize> = slopes
+            .iter()
+            .map(|&s| map.count_trees(s, Wrap::Horizontal))
+            .collect();
+        assert_eq!(trees, vec![2, 7, 3, 4, 2]);
+        assert_eq!(map.product(&slopes, Wrap::Horizontal), 336);
+        // the same direction, but only every other place on it
+        assert_eq!(map.count_trees(slope("1,1"), Wrap::Horizontal), 2);
+        assert_eq!(map.count_trees(slope("2,2"), Wrap::Horizontal), 1);
+    }
+
+    #[test]
+    fn test_slopes() {
+        assert_eq!(slope("3"), slope("3,1"));
+        assert_ne!(slope("6,2"), slope("3"));
+        assert_eq!(slope("-2,2").to_string(), "right -2, down 2");
+        assert!(Slope::from_str("1,0").is_err());
+        assert!(Slope::from_str("a").is_err());
+    }
+
+    #[test]
+    fn test_wrap_modes() {
+        let map = Map::from_str("#..\n.#.\n..#\n#..").unwrap();
+        // down the diagonal, then off the bottom
+        assert_eq!(map.count_trees(slope("1"), Wrap::Horizontal), 4);
+        assert_eq!(map.count_trees(slope("1"), Wrap::Bounded), 3);
+        // off the right side after the diagonal
+        assert_eq!(map.count_trees(slope("1"), Wrap::Vertical), 3);
+        // back at the start after going over every place once
+        assert_eq!(map.count_trees(slope("1"), Wrap::Both), 4);
+        assert_eq!(map.count_trees(slope("-1"), Wrap::Bounded), 1);
+        assert_eq!(map.count_trees(slope("-1"), Wrap::Horizontal), 2);
+    }
+
+    #[test]
+    fn test_fewest_trees() {
+        let map = Map::from_str(EXAMPLE).unwrap();
+        let (best, trees) = map.fewest_trees(3, 2, Wrap::Horizontal).unwrap();
+        assert_eq!((best, trees), (slope("2"), 1));
+        // as few trees, but going down two rows at a time
+        assert_eq!(map.count_trees(slope("-3,2"), Wrap::Horizontal), 1);
+        assert_eq!(map.count_trees(slope("0,2"), Wrap::Horizontal), 1);
+    }
+}
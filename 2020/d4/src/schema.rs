@@ -0,0 +1,373 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    // inclusive range
+    Int(u32, u32),
+    // a number directly followed by one of the units, each with its own range
+    Measure(Vec<(String, u32, u32)>),
+    // '#' followed by 6 lowercase hex digits
+    Color,
+    OneOf(Vec<String>),
+    Digits(usize),
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub kind: FieldType,
+    pub required: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    Missing,
+    NotANumber,
+    OutOfRange(u32, u32),
+    UnknownUnit,
+    NotAColor,
+    NotAllowed,
+    WrongLength(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub value: Option<String>,
+    pub problem: Problem,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value.as_deref().unwrap_or("");
+        match &self.problem {
+            Problem::Missing => write!(f, "{} is missing", self.field),
+            Problem::NotANumber => write!(f, "{} '{}' is not a number", self.field, value),
+            Problem::OutOfRange(min, max) => {
+                write!(f, "{} '{}' is not in {}-{}", self.field, value, min, max)
+            }
+            Problem::UnknownUnit => write!(f, "{} '{}' has no known unit", self.field, value),
+            Problem::NotAColor => write!(f, "{} '{}' is not a color", self.field, value),
+            Problem::NotAllowed => write!(f, "{} '{}' is not allowed", self.field, value),
+            Problem::WrongLength(n) => write!(f, "{} '{}' is not {} digits", self.field, value, n),
+        }
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn check_range(value: &str, min: u32, max: u32) -> Result<(), Problem> {
+    if !is_number(value) {
+        return Err(Problem::NotANumber);
+    }
+    match value.parse::<u32>() {
+        Ok(v) if (min..=max).contains(&v) => Ok(()),
+        _ => Err(Problem::OutOfRange(min, max)),
+    }
+}
+
+impl FieldType {
+    pub fn check(&self, value: &str) -> Result<(), Problem> {
+        match self {
+            FieldType::Int(min, max) => check_range(value, *min, *max),
+            FieldType::Measure(units) => {
+                let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+                let (number, unit) = value.split_at(digits);
+                match units.iter().find(|(u, _, _)| u == unit) {
+                    Some(&(_, min, max)) => check_range(number, min, max),
+                    None => Err(Problem::UnknownUnit),
+                }
+            }
+            FieldType::Color => match value.strip_prefix('#') {
+                Some(hex)
+                    if hex.len() == 6
+                        && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) =>
+                {
+                    Ok(())
+                }
+                _ => Err(Problem::NotAColor),
+            },
+            FieldType::OneOf(allowed) if allowed.iter().any(|a| a == value) => Ok(()),
+            FieldType::OneOf(_) => Err(Problem::NotAllowed),
+            FieldType::Digits(n) if value.len() == *n && is_number(value) => Ok(()),
+            FieldType::Digits(n) => Err(Problem::WrongLength(*n)),
+            FieldType::Any => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchemaParseErr(pub String);
+
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+    let (min, max) = s.split_once('-')?;
+    Some((min.parse().ok()?, max.parse().ok()?))
+}
+
+impl FromStr for FieldType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let words: Vec<&str> = s.split_ascii_whitespace().collect();
+        match words.as_slice() {
+            ["int", range] => parse_range(range)
+                .map(|(min, max)| FieldType::Int(min, max))
+                .ok_or(()),
+            ["measure", ..] => s["measure".len()..]
+                .split('|')
+                .map(
+                    |unit| match unit.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+                        [range, unit] => {
+                            parse_range(range).map(|(min, max)| (unit.to_string(), min, max))
+                        }
+                        _ => None,
+                    },
+                )
+                .collect::<Option<_>>()
+                .map(FieldType::Measure)
+                .ok_or(()),
+            ["color"] => Ok(FieldType::Color),
+            ["one", "of", allowed @ ..] if !allowed.is_empty() => Ok(FieldType::OneOf(
+                allowed.iter().map(|a| a.to_string()).collect(),
+            )),
+            ["digits", n] => n.parse().map(FieldType::Digits).map_err(|_| ()),
+            ["any"] => Ok(FieldType::Any),
+            _ => Err(()),
+        }
+    }
+}
+
+// One field per line, as `name: type`, or `name?: type` if it is optional:
+//   int 1920-2002
+//   measure 150-193 cm | 59-76 in
+//   color
+//   one of amb blu brn
+//   digits 9
+//   any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema(Vec<Field>);
+
+impl FromStr for Schema {
+    type Err = SchemaParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let err = || SchemaParseErr(line.to_string());
+                let (name, kind) = line.split_once(':').ok_or_else(err)?;
+                let (name, required) = match name.strip_suffix('?') {
+                    Some(name) => (name, false),
+                    None => (name, true),
+                };
+                Ok(Field {
+                    name: name.trim().to_string(),
+                    kind: kind.parse().map_err(|_| err())?,
+                    required,
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Schema)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    pub fields: Vec<(String, String)>,
+}
+
+impl Record {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RecordParseErr {
+    // index of the record in the input
+    pub record: usize,
+    pub pair: String,
+}
+
+// Records are separated by blank lines, and hold `name:value` pairs
+// separated by any whitespace.
+pub fn parse_records(s: &str) -> Result<Vec<Record>, RecordParseErr> {
+    groups::groups(s)
+        .iter()
+        .enumerate()
+        .map(|(record, lines)| {
+            let fields = lines
+                .iter()
+                .flat_map(|line| line.split_ascii_whitespace())
+                .map(|pair| {
+                    let (name, value) = pair.split_once(':').ok_or_else(|| RecordParseErr {
+                        record,
+                        pair: pair.to_string(),
+                    })?;
+                    Ok((name.to_string(), value.to_string()))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Record { fields })
+        })
+        .collect()
+}
+
+impl Schema {
+    // Only checks that required fields are there.
+    pub fn check_presence(&self, record: &Record) -> Result<(), Vec<FieldError>> {
+        self.check(record, false)
+    }
+
+    pub fn validate(&self, record: &Record) -> Result<(), Vec<FieldError>> {
+        self.check(record, true)
+    }
+
+    fn check(&self, record: &Record, check_values: bool) -> Result<(), Vec<FieldError>> {
+        let errors: Vec<FieldError> = self
+            .0
+            .iter()
+            .filter_map(|field| {
+                let value = record.get(&field.name);
+                let problem = match value {
+                    None if field.required => Problem::Missing,
+                    Some(value) if check_values => field.kind.check(value).err()?,
+                    _ => return None,
+                };
+                Some(FieldError {
+                    field: field.name.clone(),
+                    value: value.map(str::to_string),
+                    problem,
+                })
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "byr: int 1920-2002
+        hgt: measure 150-193 cm | 59-76 in
+        hcl: color
+        ecl: one of amb blu brn gry grn hzl oth
+        pid: digits 9
+        cid?: any";
+
+    fn check(kind: &str, value: &str) -> Result<(), Problem> {
+        FieldType::from_str(kind).unwrap().check(value)
+    }
+
+    #[test]
+    fn test_field_types() {
+        assert_eq!(check("int 1920-2002", "2002"), Ok(()));
+        assert_eq!(
+            check("int 1920-2002", "2003"),
+            Err(Problem::OutOfRange(1920, 2002))
+        );
+        assert_eq!(check("int 1920-2002", "+1999"), Err(Problem::NotANumber));
+
+        let height = "measure 150-193 cm | 59-76 in";
+        assert_eq!(check(height, "60in"), Ok(()));
+        assert_eq!(check(height, "190cm"), Ok(()));
+        assert_eq!(check(height, "190in"), Err(Problem::OutOfRange(59, 76)));
+        assert_eq!(check(height, "190"), Err(Problem::UnknownUnit));
+        assert_eq!(check(height, "cm"), Err(Problem::NotANumber));
+
+        assert_eq!(check("color", "#123abc"), Ok(()));
+        assert_eq!(check("color", "#123abz"), Err(Problem::NotAColor));
+        assert_eq!(check("color", "#123abcd"), Err(Problem::NotAColor));
+        assert_eq!(check("color", "123abc"), Err(Problem::NotAColor));
+
+        assert_eq!(check("one of brn amb", "brn"), Ok(()));
+        assert_eq!(check("one of brn amb", "wat"), Err(Problem::NotAllowed));
+
+        assert_eq!(check("digits 9", "000000001"), Ok(()));
+        assert_eq!(
+            check("digits 9", "0123456789"),
+            Err(Problem::WrongLength(9))
+        );
+        assert_eq!(check("digits 9", "01234567a"), Err(Problem::WrongLength(9)));
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        assert_eq!(schema.0.len(), 6);
+        assert_eq!(
+            schema.0[5],
+            Field {
+                name: "cid".to_string(),
+                kind: FieldType::Any,
+                required: false
+            }
+        );
+        assert_eq!(
+            Schema::from_str("byr int 1920-2002"),
+            Err(SchemaParseErr("byr int 1920-2002".to_string()))
+        );
+        assert!(Schema::from_str("hgt: measure 150-193").is_err());
+        assert!(Schema::from_str("ecl: one of").is_err());
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let records = parse_records("a:1 b:2\nc:3\n\n\nd:#4").unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("c"), Some("3"));
+        assert_eq!(records[1].get("d"), Some("#4"));
+        assert_eq!(
+            parse_records("a:1\n\nb"),
+            Err(RecordParseErr {
+                record: 1,
+                pair: "b".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let records =
+            parse_records("byr:1980 hgt:74in hcl:#623a2f ecl:grn pid:087499704\n\nbyr:2003 hgt:74 ecl:grn pid:087499704 cid:1")
+                .unwrap();
+
+        assert_eq!(schema.validate(&records[0]), Ok(()));
+        assert_eq!(
+            schema.validate(&records[1]),
+            Err(vec![
+                FieldError {
+                    field: "byr".to_string(),
+                    value: Some("2003".to_string()),
+                    problem: Problem::OutOfRange(1920, 2002)
+                },
+                FieldError {
+                    field: "hgt".to_string(),
+                    value: Some("74".to_string()),
+                    problem: Problem::UnknownUnit
+                },
+                FieldError {
+                    field: "hcl".to_string(),
+                    value: None,
+                    problem: Problem::Missing
+                },
+            ])
+        );
+        assert_eq!(schema.check_presence(&records[1]).unwrap_err().len(), 1);
+    }
+}
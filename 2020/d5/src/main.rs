@@ -1,98 +1,76 @@
-use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::path::Path;
 
-type Seat = (u16, u16);
+mod pass;
 
-fn iterate(seq: &str, lower_char: char, upper_bound: f64) -> u16 {
-    let range = seq
-        .chars()
-        .fold((0.0f64, upper_bound), |(lower, upper), c| {
-            if c == lower_char {
-                (lower, (lower + (upper - lower) / 2.0).floor())
-            } else {
-                ((lower + (upper - lower) / 2.0).ceil(), upper)
-            }
-        });
+use pass::Layout;
 
-    range.0 as u16
-}
-
-fn calc_seat(raw_seat: &str) -> Seat {
-    (
-        iterate(&raw_seat[0..=6], 'F', 127.0f64),
-        iterate(&raw_seat[7..=9], 'L', 7.0f64),
-    )
-}
-
-fn calc_id((row, col): Seat) -> u16 {
-    row * 8 + col
-}
-
-fn parse_ids<P>(path: P) -> Vec<u16>
+fn parse_ids<P>(path: P, layout: &Layout) -> Vec<u32>
 where
     P: AsRef<Path>,
 {
     fs::read_to_string(path)
         .unwrap()
         .lines()
-        .map(calc_seat)
-        .map(calc_id)
+        .enumerate()
+        .map(|(i, l)| {
+            layout
+                .decode_id(l)
+                .unwrap_or_else(|e| panic!("line {}: {}", i + 1, e))
+        })
         .collect()
 }
 
 fn part1() {
-    let max = parse_ids("input.txt").iter().max().unwrap().to_owned();
+    let max = parse_ids("input.txt", &Layout::default())
+        .into_iter()
+        .max()
+        .unwrap();
     println!("{}", max);
 }
 
+// Our seat is the only one missing with both neighbours taken.
 fn part2() {
-    let ids: HashSet<_> = parse_ids("input.txt").iter().copied().collect();
-    let expected_ids: HashSet<u16> = (1..=127 * 8 + 7).collect();
-
-    let mut missing: Vec<u16> = expected_ids.difference(&ids).copied().collect();
-
-    missing.sort();
-
-    let got = missing
-        .iter()
-        .zip(missing.iter().skip(1))
-        .skip_while(|(&x1, &x2)| x2 == x1 + 1)
-        .next()
-        .unwrap()
-        .1;
+    let ids = parse_ids("input.txt", &Layout::default());
+    for gap in pass::gaps(&ids) {
+        if gap.start() == gap.end() {
+            println!("{}", gap.start());
+        }
+    }
+}
 
-    println!("{}", got);
+fn gaps() {
+    let layout = Layout::default();
+    for gap in pass::gaps(&parse_ids("input.txt", &layout)) {
+        let (start, end) = (layout.seat(*gap.start()), layout.seat(*gap.end()));
+        println!(
+            "{}-{}: row {} col {} to row {} col {}",
+            gap.start(),
+            gap.end(),
+            start.0,
+            start.1,
+            end.0,
+            end.1
+        );
+    }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let layout = Layout::default();
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_calc_seat() {
-        dbg!(calc_seat("FBFBBFFRLR"));
-        assert!(calc_seat("FBFBBFFRLR") == (44, 5));
-        assert!(calc_seat("BFFFBBFRRR") == (70, 7));
-        assert!(calc_seat("FFFBBBFRRR") == (14, 7));
-        assert!(calc_seat("BBFFBBFRLL") == (102, 4));
-    }
-
-    #[test]
-    fn test_calc_id() {
-        assert!(calc_id((44, 5)) == 357);
-        assert!(calc_id((70, 7)) == 567);
-        assert!(calc_id((14, 7)) == 119);
-        assert!(calc_id((102, 4)) == 820);
+        "gaps" => gaps(),
+        "encode" => match layout.encode(args[2].parse().unwrap()) {
+            Ok(code) => println!("{}", code),
+            Err(e) => println!("{}", e),
+        },
+        "decode" => match layout.decode(&args[2]) {
+            Ok(seat) => println!("row {} col {}: {}", seat.0, seat.1, layout.id(seat)),
+            Err(e) => println!("{}", e),
+        },
+        _ => println!("Must pass either '1', '2', 'gaps', 'encode <id>' or 'decode <pass>'."),
     }
 }
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+pub type Seat = (u32, u32);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PassErr {
+    WrongLength { expected: usize, found: usize },
+    // position of the character in the code
+    BadChar { pos: usize, c: char },
+    // the ID does not fit in the layout
+    OutOfRange(u32),
+}
+
+impl fmt::Display for PassErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassErr::WrongLength { expected, found } => {
+                write!(f, "expected {} characters, found {}", expected, found)
+            }
+            PassErr::BadChar { pos, c } => write!(f, "unexpected '{}' at {}", c, pos),
+            PassErr::OutOfRange(id) => write!(f, "no seat has ID {}", id),
+        }
+    }
+}
+
+// A boarding pass is a binary number: F/B for the row bits, then L/R for the
+// column bits, B and R being ones. The seat ID is that same number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    row_bits: u32,
+    col_bits: u32,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::new(7, 3).unwrap()
+    }
+}
+
+impl Layout {
+    // IDs are u32s, so a layout has at most 31 bits, leaving room to count
+    // every seat.
+    pub fn new(row_bits: u32, col_bits: u32) -> Option<Self> {
+        if row_bits.checked_add(col_bits)? > 31 {
+            return None;
+        }
+        Some(Layout { row_bits, col_bits })
+    }
+
+    fn len(&self) -> usize {
+        (self.row_bits + self.col_bits) as usize
+    }
+
+    // Number of seats on the plane.
+    pub fn seats(&self) -> u32 {
+        1 << (self.row_bits + self.col_bits)
+    }
+
+    pub fn decode_id(&self, code: &str) -> Result<u32, PassErr> {
+        let found = code.chars().count();
+        if found != self.len() {
+            return Err(PassErr::WrongLength {
+                expected: self.len(),
+                found,
+            });
+        }
+        code.chars().enumerate().try_fold(0, |id, (pos, c)| {
+            let bit = match (pos < self.row_bits as usize, c) {
+                (true, 'F') | (false, 'L') => 0,
+                (true, 'B') | (false, 'R') => 1,
+                _ => return Err(PassErr::BadChar { pos, c }),
+            };
+            Ok(id << 1 | bit)
+        })
+    }
+
+    pub fn decode(&self, code: &str) -> Result<Seat, PassErr> {
+        self.decode_id(code).map(|id| self.seat(id))
+    }
+
+    pub fn id(&self, (row, col): Seat) -> u32 {
+        row << self.col_bits | col
+    }
+
+    pub fn seat(&self, id: u32) -> Seat {
+        (id >> self.col_bits, id & ((1 << self.col_bits) - 1))
+    }
+
+    pub fn encode(&self, id: u32) -> Result<String, PassErr> {
+        if id >= self.seats() {
+            return Err(PassErr::OutOfRange(id));
+        }
+        Ok((0..self.len())
+            .rev()
+            .map(|bit| {
+                let one = id >> bit & 1 == 1;
+                match (bit < self.col_bits as usize, one) {
+                    (false, false) => 'F',
+                    (false, true) => 'B',
+                    (true, false) => 'L',
+                    (true, true) => 'R',
+                }
+            })
+            .collect())
+    }
+}
+
+// Every run of missing IDs between the lowest and the highest one taken.
+pub fn gaps(ids: &[u32]) -> Vec<RangeInclusive<u32>> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    ids.windows(2)
+        .filter(|pair| pair[1] > pair[0] + 1)
+        .map(|pair| pair[0] + 1..=pair[1] - 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let layout = Layout::default();
+        assert_eq!(layout.decode("FBFBBFFRLR"), Ok((44, 5)));
+        assert_eq!(layout.decode("BFFFBBFRRR"), Ok((70, 7)));
+        assert_eq!(layout.decode("FFFBBBFRRR"), Ok((14, 7)));
+        assert_eq!(layout.decode("BBFFBBFRLL"), Ok((102, 4)));
+        assert_eq!(layout.decode_id("BBFFBBFRLL"), Ok(820));
+    }
+
+    #[test]
+    fn test_id() {
+        let layout = Layout::default();
+        assert_eq!(layout.id((44, 5)), 357);
+        assert_eq!(layout.id((70, 7)), 567);
+        assert_eq!(layout.id((14, 7)), 119);
+        assert_eq!(layout.id((102, 4)), 820);
+        assert_eq!(layout.seat(820), (102, 4));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let layout = Layout::default();
+        assert_eq!(
+            layout.decode("FBFBBFFRL"),
+            Err(PassErr::WrongLength {
+                expected: 10,
+                found: 9
+            })
+        );
+        // rows and columns use different letters
+        assert_eq!(
+            layout.decode("FBFBBFLRLR"),
+            Err(PassErr::BadChar { pos: 6, c: 'L' })
+        );
+        assert_eq!(
+            layout.decode("FBFBBFFRLB"),
+            Err(PassErr::BadChar { pos: 9, c: 'B' })
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let layout = Layout::default();
+        assert_eq!(layout.encode(357), Ok("FBFBBFFRLR".to_string()));
+        assert_eq!(layout.encode(1024), Err(PassErr::OutOfRange(1024)));
+        for id in 0..layout.seats() {
+            assert_eq!(layout.decode_id(&layout.encode(id).unwrap()), Ok(id));
+        }
+
+        let small = Layout::new(2, 1).unwrap();
+        assert_eq!(small.encode(5), Ok("BFR".to_string()));
+        assert_eq!(small.decode("BFR"), Ok((2, 1)));
+    }
+
+    #[test]
+    fn test_layout_size() {
+        assert_eq!(Layout::new(32, 0), None);
+        assert_eq!(Layout::new(16, 16), None);
+        assert_eq!(Layout::new(u32::MAX, 1), None);
+
+        let widest = Layout::new(20, 11).unwrap();
+        assert_eq!(widest.seats(), 1 << 31);
+        let last = widest.seats() - 1;
+        assert_eq!(widest.seat(last), ((1 << 20) - 1, (1 << 11) - 1));
+        assert_eq!(widest.id(widest.seat(last)), last);
+        assert_eq!(widest.decode_id(&widest.encode(last).unwrap()), Ok(last));
+        assert_eq!(widest.encode(1 << 31), Err(PassErr::OutOfRange(1 << 31)));
+    }
+
+    #[test]
+    fn test_gaps() {
+        assert_eq!(gaps(&[5, 3, 9, 4, 6, 11]), vec![7..=8, 10..=10]);
+        assert!(gaps(&[2, 1, 3]).is_empty());
+        assert!(gaps(&[]).is_empty());
+    }
+}
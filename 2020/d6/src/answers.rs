@@ -0,0 +1,201 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr};
+
+// Questions a to z, one bit each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Questions(u32);
+
+impl Questions {
+    pub const ALL: Questions = Questions((1 << 26) - 1);
+
+    // Fails on the first character that is not a question.
+    pub fn from_line(line: &str) -> Result<Self, char> {
+        line.chars().try_fold(Questions::default(), |questions, c| {
+            if c.is_ascii_lowercase() {
+                Ok(Questions(questions.0 | 1 << (c as u8 - b'a')))
+            } else {
+                Err(c)
+            }
+        })
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn contains(self, question: char) -> bool {
+        question.is_ascii_lowercase() && self.0 & 1 << (question as u8 - b'a') != 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        ('a'..='z').filter(move |&q| self.contains(q))
+    }
+}
+
+impl BitOr for Questions {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Questions(self.0 | other.0)
+    }
+}
+
+impl BitAnd for Questions {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Questions(self.0 & other.0)
+    }
+}
+
+impl fmt::Display for Questions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|q| write!(f, "{}", q))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseErr {
+    pub group: usize,
+    pub person: usize,
+    pub c: char,
+}
+
+// Questions answered yes by each person of a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group(Vec<Questions>);
+
+impl Group {
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    // Questions anyone answered.
+    pub fn anyone(&self) -> Questions {
+        self.0.iter().fold(Questions::default(), |acc, &q| acc | q)
+    }
+
+    // Questions everyone answered.
+    pub fn everyone(&self) -> Questions {
+        self.0.iter().fold(Questions::ALL, |acc, &q| acc & q)
+    }
+
+    // How many people answered each question, from a to z.
+    pub fn frequencies(&self) -> [usize; 26] {
+        let mut frequencies = [0; 26];
+        for person in &self.0 {
+            for q in person.iter() {
+                frequencies[(q as u8 - b'a') as usize] += 1;
+            }
+        }
+        frequencies
+    }
+
+    fn answered_where(&self, keep: impl Fn(usize) -> bool) -> Questions {
+        let bits = self
+            .frequencies()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &n)| n > 0 && keep(n))
+            .fold(0, |acc, (i, _)| acc | 1 << i);
+        Questions(bits)
+    }
+
+    // Questions answered by exactly `k` people.
+    pub fn answered_by(&self, k: usize) -> Questions {
+        self.answered_where(|n| n == k)
+    }
+
+    // Questions answered by at least a share `p` of the group, and by at
+    // least one person.
+    pub fn answered_by_share(&self, p: f64) -> Questions {
+        let size = self.size() as f64;
+        // so that 0.7 of 10 people still means 7
+        self.answered_where(|n| n as f64 >= p * size - 1e-9)
+    }
+}
+
+pub fn parse_groups(s: &str) -> Result<Vec<Group>, ParseErr> {
+    groups::groups(s)
+        .iter()
+        .enumerate()
+        .map(|(group, lines)| {
+            lines
+                .iter()
+                .enumerate()
+                .map(|(person, line)| {
+                    Questions::from_line(line).map_err(|c| ParseErr { group, person, c })
+                })
+                .collect::<Result<_, _>>()
+                .map(Group)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+    #[test]
+    fn test_union_and_intersection() {
+        let groups = parse_groups(EXAMPLE).unwrap();
+        assert_eq!(groups.len(), 5);
+        let anyone: Vec<usize> = groups.iter().map(|g| g.anyone().len()).collect();
+        assert_eq!(anyone, vec![3, 3, 3, 1, 1]);
+        let everyone: Vec<usize> = groups.iter().map(|g| g.everyone().len()).collect();
+        assert_eq!(everyone, vec![3, 0, 1, 1, 1]);
+        assert_eq!(groups[2].everyone().to_string(), "a");
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let groups = parse_groups(EXAMPLE).unwrap();
+        let frequencies = groups[2].frequencies();
+        assert_eq!(&frequencies[..4], &[2, 1, 1, 0]);
+
+        let group = &parse_groups("abc\nab\nb\nbd").unwrap()[0];
+        assert_eq!(group.answered_by(1).to_string(), "cd");
+        assert_eq!(group.answered_by(2).to_string(), "a");
+        assert_eq!(group.answered_by(4), group.everyone());
+        assert_eq!(group.answered_by(0), Questions::default());
+        assert_eq!(group.answered_by_share(0.5).to_string(), "ab");
+        assert_eq!(group.answered_by_share(0.0), group.anyone());
+        assert_eq!(group.answered_by_share(1.0), group.everyone());
+    }
+
+    #[test]
+    fn test_share_rounding() {
+        let lines: Vec<&str> = (0..10).map(|i| if i < 7 { "ab" } else { "b" }).collect();
+        let group = &parse_groups(&lines.join("\n")).unwrap()[0];
+        assert_eq!(group.answered_by_share(0.7).to_string(), "ab");
+        assert_eq!(group.answered_by_share(0.71).to_string(), "b");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_groups("ab\n\nc\nxY"),
+            Err(ParseErr {
+                group: 1,
+                person: 1,
+                c: 'Y'
+            })
+        );
+    }
+}
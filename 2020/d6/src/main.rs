@@ -1,72 +1,65 @@
-use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Default)]
-struct Group {
-    nlines: usize,
-    questions: HashMap<char, usize>,
-}
-
-impl Group {
-    fn new() -> Self {
-        Self::default()
-    }
+mod answers;
 
-    fn add_line(&mut self, line: &str) {
-        self.nlines += 1;
-        for c in line.chars() {
-            let count = self.questions.entry(c).or_insert(0);
-            *count += 1;
-        }
-    }
-
-    fn n_total_questions(&self) -> usize {
-        self.questions.len()
-    }
-
-    fn n_common_questions(&self) -> usize {
-        self.questions
-            .values()
-            .filter(|&v| v == &self.nlines)
-            .count()
-    }
-}
+use answers::Group;
 
 fn parse_groups<P>(path: P) -> Vec<Group>
 where
     P: AsRef<Path>,
 {
-    let mut groups = vec![Group::new()];
-    for line in fs::read_to_string(path).unwrap().lines() {
-        if line == "" {
-            groups.push(Group::new());
-        } else {
-            groups.last_mut().unwrap().add_line(line);
-        }
-    }
-
-    groups
+    answers::parse_groups(&fs::read_to_string(path).unwrap()).unwrap()
 }
 
 fn part1() {
     let groups = parse_groups("input.txt");
-    let res: usize = groups.iter().map(Group::n_total_questions).sum();
+    let res: usize = groups.iter().map(|g| g.anyone().len()).sum();
     println!("{}", res);
 }
 
 fn part2() {
     let groups = parse_groups("input.txt");
-    let res: usize = groups.iter().map(Group::n_common_questions).sum();
+    let res: usize = groups.iter().map(|g| g.everyone().len()).sum();
     println!("{}", res);
 }
 
+fn exactly(args: &[String]) {
+    let k: usize = args[2].parse().unwrap();
+    let groups = parse_groups("input.txt");
+    let res: usize = groups.iter().map(|g| g.answered_by(k).len()).sum();
+    println!("{}", res);
+}
+
+fn share(args: &[String]) {
+    let p: f64 = args[2].parse().unwrap();
+    let groups = parse_groups("input.txt");
+    let res: usize = groups.iter().map(|g| g.answered_by_share(p).len()).sum();
+    println!("{}", res);
+}
+
+// How many people answered each question, over all groups.
+fn frequencies() {
+    let mut total = [0; 26];
+    for group in parse_groups("input.txt") {
+        for (t, n) in total.iter_mut().zip(group.frequencies()) {
+            *t += n;
+        }
+    }
+    for (q, n) in ('a'..='z').zip(total) {
+        println!("{}: {}", q, n);
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "exactly" => exactly(&args),
+        "share" => share(&args),
+        "freq" => frequencies(),
+        _ => println!("Must pass either '1', '2', 'exactly <k>', 'share <p>' or 'freq'."),
     }
 }
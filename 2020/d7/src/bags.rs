@@ -0,0 +1,244 @@
+use std::collections::{HashMap, VecDeque};
+
+use dag::{Cycle, Dag};
+
+use crate::Rule;
+
+// Edges go from each bag to the bags it must contain, weighted by how many.
+type BagGraph = Dag<String, u64>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Containment {
+    // from the outer bag to the inner one, both included
+    pub bags: Vec<String>,
+    // how many of the inner bag this path puts in the outer one
+    pub count: u64,
+}
+
+pub struct Bags {
+    graph: BagGraph,
+    // total number of bags inside each bag, or the cycle that bag reaches
+    inside: HashMap<String, Result<u64, Cycle<String>>>,
+}
+
+impl Bags {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        let mut graph = BagGraph::new();
+        for rule in rules {
+            graph.add_node(rule.bag_name.clone());
+            for contain in rule.contains {
+                graph.add_edge(rule.bag_name.clone(), contain.name, contain.count);
+            }
+        }
+        let mut inside = HashMap::new();
+        for bag in graph.nodes() {
+            // only the error matters here, and it is kept with every bag
+            let _ = count_inside(&graph, &mut inside, bag, &mut Vec::new());
+        }
+        Bags { graph, inside }
+    }
+
+    // Number of different bags that end up holding `bag`.
+    pub fn count_containers(&self, bag: &str) -> usize {
+        self.graph.ancestors(bag).len()
+    }
+
+    // Fails if `bag` ends up holding a bag that has to contain itself.
+    // Bags that cannot reach such a cycle still get counted.
+    pub fn count_inside(&self, bag: &str) -> Result<u64, Cycle<String>> {
+        self.inside.get(bag).cloned().unwrap_or(Ok(0))
+    }
+
+    // The way of nesting `inner` in `outer` with the fewest bags in between.
+    // A bag only holds itself through a cycle.
+    pub fn shortest_path(&self, outer: &str, inner: &str) -> Option<Containment> {
+        let mut previous: HashMap<&str, (&str, u64)> = HashMap::new();
+        let mut to_visit = VecDeque::from([outer]);
+        while let Some(bag) = to_visit.pop_front() {
+            for (child, &n) in self.graph.children(bag) {
+                let child = child.as_str();
+                if previous.contains_key(child) {
+                    continue;
+                }
+                previous.insert(child, (bag, n));
+                if child == inner {
+                    let mut bags = vec![inner.to_string()];
+                    let mut count = 1;
+                    let mut current = inner;
+                    loop {
+                        let (parent, n) = previous[current];
+                        bags.push(parent.to_string());
+                        count *= n;
+                        current = parent;
+                        if current == outer {
+                            break;
+                        }
+                    }
+                    bags.reverse();
+                    return Some(Containment { bags, count });
+                }
+                to_visit.push_back(child);
+            }
+        }
+        None
+    }
+
+    // Graphviz description of the rules, sorted so that it is stable.
+    pub fn to_dot(&self) -> String {
+        let mut bags: Vec<&String> = self.graph.nodes().collect();
+        bags.sort();
+
+        let mut dot = String::from("digraph bags {\n");
+        for bag in bags {
+            let mut children: Vec<(&String, &u64)> = self.graph.children(bag).collect();
+            children.sort();
+            if children.is_empty() {
+                dot.push_str(&format!("    \"{}\";\n", bag));
+            }
+            for (child, count) in children {
+                dot.push_str(&format!(
+                    "    \"{}\" -> \"{}\" [label={}];\n",
+                    bag, child, count
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Depth-first, with the bags opened on the way down to `bag` in `path`, so
+// that getting back to one of them is a cycle. Every bag on the way to a
+// cycle keeps that cycle.
+fn count_inside(
+    graph: &BagGraph,
+    inside: &mut HashMap<String, Result<u64, Cycle<String>>>,
+    bag: &String,
+    path: &mut Vec<String>,
+) -> Result<u64, Cycle<String>> {
+    if let Some(total) = inside.get(bag) {
+        return total.clone();
+    }
+    if let Some(start) = path.iter().position(|b| b == bag) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(bag.clone());
+        return Err(Cycle(cycle));
+    }
+
+    path.push(bag.clone());
+    let total = graph.children(bag).try_fold(0, |total, (child, &count)| {
+        Ok(total + count * (1 + count_inside(graph, inside, child, path)?))
+    });
+    path.pop();
+    inside.insert(bag.clone(), total.clone());
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    fn bags(s: &str) -> Bags {
+        Bags::new(s.lines().map(|l| Rule::from_str(l).unwrap()).collect())
+    }
+
+    #[test]
+    fn test_count_containers() {
+        let bags = bags(EXAMPLE);
+        assert_eq!(bags.count_containers("shiny gold"), 4);
+        assert_eq!(bags.count_containers("light red"), 0);
+        assert_eq!(bags.count_containers("plaid purple"), 0);
+    }
+
+    #[test]
+    fn test_count_inside() {
+        let bags = bags(EXAMPLE);
+        assert_eq!(bags.count_inside("shiny gold"), Ok(32));
+        assert_eq!(bags.count_inside("faded blue"), Ok(0));
+        assert_eq!(bags.count_inside("light red"), Ok(186));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let bags = bags(
+            "light red bags contain 1 bright white bag.
+bright white bags contain 2 light red bags, 1 faded blue bag.
+faded blue bags contain no other bags.
+dark olive bags contain 3 faded blue bags.",
+        );
+        // bags out of the cycle's reach still have a count
+        assert_eq!(bags.count_inside("faded blue"), Ok(0));
+        assert_eq!(bags.count_inside("dark olive"), Ok(3));
+        assert_eq!(
+            bags.count_inside("light red"),
+            Err(Cycle(vec![
+                "light red".to_string(),
+                "bright white".to_string(),
+                "light red".to_string()
+            ]))
+        );
+        assert_eq!(bags.count_containers("faded blue"), 3);
+        assert_eq!(
+            bags.shortest_path("light red", "faded blue").unwrap().bags,
+            vec!["light red", "bright white", "faded blue"]
+        );
+        assert_eq!(
+            bags.shortest_path("bright white", "bright white"),
+            Some(Containment {
+                bags: vec![
+                    "bright white".to_string(),
+                    "light red".to_string(),
+                    "bright white".to_string()
+                ],
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let bags = bags(EXAMPLE);
+        assert_eq!(
+            bags.shortest_path("light red", "faded blue"),
+            Some(Containment {
+                bags: vec![
+                    "light red".to_string(),
+                    "muted yellow".to_string(),
+                    "faded blue".to_string()
+                ],
+                count: 18
+            })
+        );
+        assert_eq!(bags.shortest_path("shiny gold", "light red"), None);
+        // no bag holds itself without a cycle
+        assert_eq!(bags.shortest_path("faded blue", "faded blue"), None);
+        assert_eq!(bags.shortest_path("shiny gold", "shiny gold"), None);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let bags = bags(
+            "bright white bags contain 1 shiny gold bag.
+shiny gold bags contain no other bags.",
+        );
+        assert_eq!(
+            bags.to_dot(),
+            "digraph bags {
+    \"bright white\" -> \"shiny gold\" [label=1];
+    \"shiny gold\";
+}
+"
+        );
+    }
+}
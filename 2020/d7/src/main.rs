@@ -1,5 +1,3 @@
-use std::collections::{HashMap, HashSet};
-use std::convert::From;
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -8,6 +6,10 @@ use std::str::FromStr;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+mod bags;
+
+use bags::Bags;
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 struct BagCount {
     name: String,
@@ -46,103 +48,6 @@ impl FromStr for Rule {
 
 type RuleSet = Vec<Rule>;
 
-#[derive(Debug, Default)]
-struct Dependencies(HashMap<String, HashSet<BagCount>>);
-
-impl Dependencies {
-    fn new() -> Self {
-        Self::default()
-    }
-
-    fn push_dep(&mut self, contained: BagCount, bag_name: String) {
-        let depends_on = self.0.entry(bag_name).or_default();
-        (*depends_on).insert(contained);
-    }
-
-    fn sum_sub_bags(&self, from: String) -> u64 {
-        let mut to_see_queue = Vec::with_capacity(self.0.len());
-
-        to_see_queue.push((1, from));
-
-        let mut total = 0;
-        while let Some((parent_count, next)) = to_see_queue.pop() {
-            total += parent_count;
-            if let Some(bags) = self.0.get(&next) {
-                for bag in bags {
-                    to_see_queue.push((parent_count * bag.count, bag.name.clone()));
-                }
-            }
-        }
-
-        total - 1
-    }
-}
-
-impl From<RuleSet> for Dependencies {
-    fn from(ruleset: RuleSet) -> Self {
-        let mut deps = Self::new();
-
-        for rule in ruleset {
-            let bag_name = rule.bag_name;
-            for contain in rule.contains {
-                deps.push_dep(contain, bag_name.clone());
-            }
-        }
-
-        deps
-    }
-}
-
-#[derive(Debug, Default)]
-struct ReverseDependencies(HashMap<String, HashSet<String>>);
-
-impl ReverseDependencies {
-    fn new() -> Self {
-        Self::default()
-    }
-
-    fn push_dep(&mut self, contained: &BagCount, bag_name: String) {
-        let depends_on = self.0.entry(contained.name.clone()).or_default();
-        (*depends_on).insert(bag_name);
-    }
-
-    fn count_reachable_from(&self, from: String) -> usize {
-        let mut seen: HashSet<&str> = HashSet::with_capacity(self.0.len());
-        let mut to_see_queue = Vec::with_capacity(self.0.len());
-
-        to_see_queue.push(from);
-
-        while let Some(next) = to_see_queue.pop() {
-            if let Some(bags) = self.0.get(&next) {
-                for bag in bags {
-                    if !seen.contains(&bag[..]) {
-                        to_see_queue.push(bag.to_string());
-                        seen.insert(bag);
-                    }
-                }
-            }
-        }
-
-        seen.len()
-    }
-}
-
-impl From<RuleSet> for ReverseDependencies {
-    fn from(ruleset: RuleSet) -> Self {
-        let mut deps = Self::new();
-
-        for rule in ruleset {
-            let bag_name = rule.bag_name;
-
-            for contain in &rule.contains {
-                deps.push_dep(contain, bag_name.clone());
-            }
-        }
-
-        deps
-    }
-}
-
 fn parse_rule_set<P>(path: P) -> RuleSet
 where
     P: AsRef<Path>,
@@ -155,16 +60,29 @@ where
 }
 
 fn part1() {
-    let res = ReverseDependencies::from(parse_rule_set("input.txt"))
-        .count_reachable_from("shiny gold".into());
+    let res = Bags::new(parse_rule_set("input.txt")).count_containers("shiny gold");
 
     println!("{}", res);
 }
 
 fn part2() {
-    let res = Dependencies::from(parse_rule_set("input.txt")).sum_sub_bags("shiny gold".into());
+    let bags = Bags::new(parse_rule_set("input.txt"));
+    match bags.count_inside("shiny gold") {
+        Ok(res) => println!("{}", res),
+        Err(cycle) => println!("Bags contain each other: {}", cycle.0.join(" -> ")),
+    }
+}
 
-    println!("{}", res);
+fn path(args: &[String]) {
+    let bags = Bags::new(parse_rule_set("input.txt"));
+    match bags.shortest_path(&args[2], &args[3]) {
+        Some(path) => println!("{} ({} bags)", path.bags.join(" > "), path.count),
+        None => println!("{} bags never hold {} bags", args[2], args[3]),
+    }
+}
+
+fn dot() {
+    print!("{}", Bags::new(parse_rule_set("input.txt")).to_dot());
 }
 
 fn main() {
@@ -172,7 +90,9 @@ fn main() {
     match &args[1][..] {
         "1" => part1(),
         "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "path" => path(&args),
+        "dot" => dot(),
+        _ => println!("Must pass either '1', '2', 'path <outer> <inner>' or 'dot'."),
     }
 }
 
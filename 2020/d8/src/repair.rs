@@ -0,0 +1,108 @@
+use crate::vm::Program;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RepairErr {
+    // the program already terminates
+    NotBroken,
+    // no single nop/jmp swap makes it terminate
+    NoFix,
+}
+
+// Which instructions lead to termination, found by walking the jumps
+// backwards from the end of the program.
+fn reaches_end(program: &Program) -> Vec<bool> {
+    let n = program.len();
+    let mut sources: Vec<Vec<usize>> = vec![Vec::new(); n + 1];
+    for (pc, instruction) in program.0.iter().enumerate() {
+        let next = instruction.next(pc);
+        if (0..=n as i64).contains(&next) {
+            sources[next as usize].push(pc);
+        }
+    }
+
+    let mut reaches = vec![false; n + 1];
+    reaches[n] = true;
+    let mut stack = vec![n];
+    while let Some(pc) = stack.pop() {
+        for &source in &sources[pc] {
+            if !reaches[source] {
+                reaches[source] = true;
+                stack.push(source);
+            }
+        }
+    }
+    reaches
+}
+
+// The instruction to swap between nop and jmp so the program terminates.
+// Up to the swap, execution follows the original loop, and the swap must
+// send it somewhere that already leads to the end. That place cannot go
+// through the swapped instruction again, or the original would have
+// terminated.
+pub fn repair(program: &Program) -> Result<usize, RepairErr> {
+    let reaches = reaches_end(program);
+    if reaches[0] {
+        return Err(RepairErr::NotBroken);
+    }
+
+    let n = program.len() as i64;
+    let mut visited = vec![false; program.len()];
+    let mut pc = 0;
+    while (0..n).contains(&pc) && !visited[pc as usize] {
+        let at = pc as usize;
+        visited[at] = true;
+        let instruction = program.0[at];
+        if let Some(flipped) = instruction.flipped() {
+            let next = flipped.next(at);
+            if (0..=n).contains(&next) && reaches[next as usize] {
+                return Ok(at);
+            }
+        }
+        pc = instruction.next(at);
+    }
+    Err(RepairErr::NoFix)
+}
+
+// Swaps the instruction found by `repair`.
+pub fn repaired(program: &Program) -> Result<Program, RepairErr> {
+    let pc = repair(program)?;
+    Ok(program.patched(pc).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{Halt, Instruction, Vm};
+    use std::str::FromStr;
+
+    fn program(s: &str) -> Program {
+        Program::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let example =
+            program("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6");
+        assert_eq!(repair(&example), Ok(7));
+        let fixed = repaired(&example).unwrap();
+        assert_eq!(fixed.0[7], Instruction::Nop(-4));
+        let mut vm = Vm::new(&fixed);
+        assert_eq!(vm.run_to_halt(), Halt::Terminated);
+        assert_eq!(vm.acc(), 8);
+    }
+
+    #[test]
+    fn test_not_repairable() {
+        assert_eq!(
+            repair(&program("acc +1\nnop +0")),
+            Err(RepairErr::NotBroken)
+        );
+        // the nop has to become a jump to the end
+        assert_eq!(repair(&program("nop +2\njmp +0")), Ok(0));
+        // jumping out of bounds does not count as terminating
+        assert_eq!(
+            repair(&program("nop +5\njmp +0\njmp -5")),
+            Err(RepairErr::NoFix)
+        );
+    }
+}
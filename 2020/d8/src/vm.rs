@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Instruction {
+    Nop(i64),
+    Acc(i64),
+    Jmp(i64),
+}
+
+impl Instruction {
+    // Swaps nop and jmp, keeping the argument.
+    pub fn flipped(self) -> Option<Self> {
+        match self {
+            Instruction::Nop(v) => Some(Instruction::Jmp(v)),
+            Instruction::Jmp(v) => Some(Instruction::Nop(v)),
+            Instruction::Acc(_) => None,
+        }
+    }
+
+    // Where execution goes after this instruction at `pc`.
+    pub fn next(self, pc: usize) -> i64 {
+        match self {
+            Instruction::Jmp(delta) => pc as i64 + delta,
+            _ => pc as i64 + 1,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Nop(v) => write!(f, "nop {:+}", v),
+            Instruction::Acc(v) => write!(f, "acc {:+}", v),
+            Instruction::Jmp(v) => write!(f, "jmp {:+}", v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseErr {
+    UnknownOp(String),
+    BadArgument(String),
+    Malformed,
+}
+
+impl FromStr for Instruction {
+    type Err = ParseErr;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (op, arg) = match line.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+            [op, arg] => (op, arg),
+            _ => return Err(ParseErr::Malformed),
+        };
+        let val = arg
+            .parse::<i64>()
+            .map_err(|_| ParseErr::BadArgument(arg.to_string()))?;
+        match op {
+            "nop" => Ok(Instruction::Nop(val)),
+            "acc" => Ok(Instruction::Acc(val)),
+            "jmp" => Ok(Instruction::Jmp(val)),
+            _ => Err(ParseErr::UnknownOp(op.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProgramParseErr {
+    pub line: usize,
+    pub err: ParseErr,
+}
+
+impl fmt::Display for ProgramParseErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.err {
+            ParseErr::UnknownOp(op) => write!(f, "line {}: unknown operation '{}'", self.line, op),
+            ParseErr::BadArgument(arg) => write!(f, "line {}: bad argument '{}'", self.line, arg),
+            ParseErr::Malformed => write!(
+                f,
+                "line {}: expected an operation and an argument",
+                self.line
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program(pub Vec<Instruction>);
+
+impl FromStr for Program {
+    type Err = ProgramParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .map(|(i, l)| {
+                l.parse()
+                    .map_err(|err| ProgramParseErr { line: i + 1, err })
+            })
+            .collect::<Result<_, _>>()
+            .map(Program)
+    }
+}
+
+impl Program {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    // A copy with the instruction at `pc` swapped between nop and jmp.
+    pub fn patched(&self, pc: usize) -> Option<Self> {
+        let mut patched = self.clone();
+        patched.0[pc] = self.0[pc].flipped()?;
+        Some(patched)
+    }
+}
+
+// Why the machine cannot go on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halt {
+    // right past the last instruction
+    Terminated,
+    // about to run the instruction at this position a second time
+    Loop(usize),
+    OutOfBounds(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted(Halt),
+    Breakpoint(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub pc: usize,
+    pub instruction: Instruction,
+    // accumulator once the instruction has run
+    pub acc: i64,
+}
+
+pub struct Vm<'a> {
+    program: &'a Program,
+    pc: i64,
+    acc: i64,
+    visited: Vec<bool>,
+    breakpoints: HashSet<usize>,
+    trace: Vec<Step>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Vm {
+            program,
+            pc: 0,
+            acc: 0,
+            visited: vec![false; program.len()],
+            breakpoints: HashSet::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    pub fn acc(&self) -> i64 {
+        self.acc
+    }
+
+    pub fn trace(&self) -> &[Step] {
+        &self.trace
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    // Runs the instruction at the current position, unless the machine has
+    // halted.
+    pub fn step(&mut self) -> Result<(), Halt> {
+        if self.pc == self.program.len() as i64 {
+            return Err(Halt::Terminated);
+        }
+        if self.pc < 0 || self.pc > self.program.len() as i64 {
+            return Err(Halt::OutOfBounds(self.pc));
+        }
+        let pc = self.pc as usize;
+        if self.visited[pc] {
+            return Err(Halt::Loop(pc));
+        }
+        self.visited[pc] = true;
+
+        let instruction = self.program.0[pc];
+        if let Instruction::Acc(val) = instruction {
+            self.acc += val;
+        }
+        self.pc = instruction.next(pc);
+        self.trace.push(Step {
+            pc,
+            instruction,
+            acc: self.acc,
+        });
+        Ok(())
+    }
+
+    // Runs until the machine halts or gets to a breakpoint. The instruction
+    // at the current position always runs, so that calling this again
+    // resumes from a breakpoint.
+    pub fn run(&mut self) -> Stop {
+        loop {
+            if let Err(halt) = self.step() {
+                return Stop::Halted(halt);
+            }
+            if self.pc >= 0 && self.breakpoints.contains(&(self.pc as usize)) {
+                return Stop::Breakpoint(self.pc as usize);
+            }
+        }
+    }
+
+    pub fn run_to_halt(&mut self) -> Halt {
+        loop {
+            if let Err(halt) = self.step() {
+                return halt;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_parse() {
+        let program = Program::from_str(EXAMPLE).unwrap();
+        assert_eq!(program.len(), 9);
+        assert_eq!(program.0[4], Instruction::Jmp(-3));
+        assert_eq!(program.0[4].to_string(), "jmp -3");
+        assert_eq!(
+            Program::from_str("nop +0\nmul +2"),
+            Err(ProgramParseErr {
+                line: 2,
+                err: ParseErr::UnknownOp("mul".to_string())
+            })
+        );
+        assert_eq!(
+            Instruction::from_str("acc x"),
+            Err(ParseErr::BadArgument("x".to_string()))
+        );
+        assert_eq!(Instruction::from_str("acc"), Err(ParseErr::Malformed));
+    }
+
+    #[test]
+    fn test_run() {
+        let program = Program::from_str(EXAMPLE).unwrap();
+        let mut vm = Vm::new(&program);
+        assert_eq!(vm.run_to_halt(), Halt::Loop(1));
+        assert_eq!(vm.acc(), 5);
+        let pcs: Vec<usize> = vm.trace().iter().map(|s| s.pc).collect();
+        assert_eq!(pcs, vec![0, 1, 2, 6, 7, 3, 4]);
+
+        let patched = program.patched(7).unwrap();
+        let mut vm = Vm::new(&patched);
+        assert_eq!(vm.run_to_halt(), Halt::Terminated);
+        assert_eq!(vm.acc(), 8);
+
+        let program = Program::from_str("jmp +2\nnop +0\njmp -5").unwrap();
+        assert_eq!(Vm::new(&program).run_to_halt(), Halt::OutOfBounds(-3));
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let program = Program::from_str(EXAMPLE).unwrap();
+        let mut vm = Vm::new(&program);
+        vm.add_breakpoint(3);
+        vm.add_breakpoint(7);
+        assert_eq!(vm.run(), Stop::Breakpoint(7));
+        assert_eq!(vm.acc(), 2);
+        assert_eq!(vm.run(), Stop::Breakpoint(3));
+        assert_eq!(vm.run(), Stop::Halted(Halt::Loop(1)));
+        assert_eq!(vm.trace().last().unwrap().acc, 5);
+    }
+}
@@ -1,14 +1,14 @@
-use std::collections::VecDeque;
 use std::env;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-use itertools::Itertools;
+mod xmas;
+
+const PREAMBLE_SIZE: usize = 25;
 
 type Packet = u64;
 type Stream = Vec<Packet>;
-type Preamble = VecDeque<Packet>;
 
 fn parse_stream<P>(path: P) -> Stream
 where
@@ -21,62 +21,33 @@ where
         .collect()
 }
 
-fn preamble_allows(preamble: &Preamble, value: Packet) -> bool {
-    preamble
-        .iter()
-        .combinations(2)
-        .map(|v| v.iter().copied().sum())
-        .find(|&s: &Packet| s == value)
-        .is_some()
-}
-
-fn find_invalid_number(stream: &Stream, preamble_size: usize) -> Option<Packet> {
-    let mut preamble: VecDeque<Packet> = stream.iter().take(preamble_size).copied().collect();
-
-    let mut found: Option<Packet> = None;
-
-    for &packet in stream.iter().skip(preamble_size) {
-        if !preamble_allows(&preamble, packet) {
-            found = Some(packet);
-            break;
-        }
-        preamble.pop_front();
-        preamble.push_back(packet)
-    }
-
-    found
+fn preamble_size(args: &[String]) -> usize {
+    args.get(2).map_or(PREAMBLE_SIZE, |s| s.parse().unwrap())
 }
 
-fn part1() {
-    let invalid = find_invalid_number(&parse_stream("input.txt"), 25);
-    println!("{}", invalid.unwrap());
+fn part1(args: &[String]) {
+    let invalid = xmas::invalid_numbers(&parse_stream("input.txt"), preamble_size(args));
+    println!("{}", invalid[0].1);
 }
 
-fn part2() {
+fn part2(args: &[String]) {
     let stream = parse_stream("input.txt");
-    let invalid = find_invalid_number(&stream, 25).unwrap();
-
-    let mut found: Option<Packet> = None;
+    let (_, invalid) = xmas::invalid_numbers(&stream, preamble_size(args))[0];
+    println!("{}", xmas::weakness(&stream, invalid).unwrap());
+}
 
-    'outer: for chunk_size in 2..stream.len() {
-        for window in stream.windows(chunk_size) {
-            if window.iter().sum::<Packet>() == invalid {
-                let val =
-                    window.iter().copied().min().unwrap() + window.iter().copied().max().unwrap();
-                found = Some(val);
-                break 'outer;
-            }
-        }
+fn list_invalid(args: &[String]) {
+    for (i, packet) in xmas::invalid_numbers(&parse_stream("input.txt"), preamble_size(args)) {
+        println!("{}: {}", i, packet);
     }
-
-    println!("{}", found.unwrap());
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     match &args[1][..] {
-        "1" => part1(),
-        "2" => part2(),
-        _ => println!("Must pass either '1' or '2'."),
+        "1" => part1(&args),
+        "2" => part2(&args),
+        "invalid" => list_invalid(&args),
+        _ => println!("Must pass either '1', '2' or 'invalid', and optionally a preamble size."),
     }
 }
@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+use crate::Packet;
+
+// Checks numbers one at a time against the `size` numbers before them. The
+// sums of every pair in the window are kept as a multiset, so sliding the
+// window only touches the pairs involving the numbers leaving and entering.
+pub struct Validator {
+    size: usize,
+    window: VecDeque<Packet>,
+    sums: HashMap<Packet, usize>,
+}
+
+impl Validator {
+    pub fn new(size: usize) -> Self {
+        Validator {
+            size,
+            window: VecDeque::with_capacity(size),
+            sums: HashMap::with_capacity(size * size / 2),
+        }
+    }
+
+    // Whether `packet` is the sum of two of the numbers before it. Numbers
+    // in the preamble are always valid.
+    pub fn push(&mut self, packet: Packet) -> bool {
+        let valid = self.window.len() < self.size || self.sums.contains_key(&packet);
+
+        if self.window.len() == self.size {
+            if let Some(oldest) = self.window.pop_front() {
+                for &other in &self.window {
+                    let count = self.sums.get_mut(&(oldest + other)).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        self.sums.remove(&(oldest + other));
+                    }
+                }
+            }
+        }
+        if self.size > 0 {
+            for &other in &self.window {
+                *self.sums.entry(packet + other).or_default() += 1;
+            }
+            self.window.push_back(packet);
+        }
+
+        valid
+    }
+}
+
+// Position and value of every number that is not the sum of two of the
+// `preamble` numbers before it.
+pub fn invalid_numbers(stream: &[Packet], preamble: usize) -> Vec<(usize, Packet)> {
+    let mut validator = Validator::new(preamble);
+    stream
+        .iter()
+        .enumerate()
+        .filter(|&(_, &packet)| !validator.push(packet))
+        .map(|(i, &packet)| (i, packet))
+        .collect()
+}
+
+// First range of at least two contiguous numbers adding up to `target`, by
+// where it ends. Numbers are never negative, so the range sum only grows
+// when moving its end forward and only shrinks when moving its start.
+pub fn contiguous_range(stream: &[Packet], target: Packet) -> Option<Range<usize>> {
+    let mut prefix = Vec::with_capacity(stream.len() + 1);
+    prefix.push(0);
+    for &packet in stream {
+        prefix.push(prefix.last().unwrap() + packet);
+    }
+
+    let mut start = 0;
+    for end in 1..prefix.len() {
+        while prefix[end] - prefix[start] > target {
+            start += 1;
+        }
+        if prefix[end] - prefix[start] == target && end - start >= 2 {
+            return Some(start..end);
+        }
+    }
+    None
+}
+
+// Sum of the smallest and largest numbers in a range adding up to `target`.
+pub fn weakness(stream: &[Packet], target: Packet) -> Option<Packet> {
+    let range = &stream[contiguous_range(stream, target)?];
+    Some(range.iter().min()? + range.iter().max()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [Packet; 20] = [
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+
+    #[test]
+    fn test_invalid_numbers() {
+        assert_eq!(invalid_numbers(&EXAMPLE, 5), vec![(14, 127)]);
+        // with a window of two, a number is only valid if it is the sum of
+        // the two right before it
+        let expected: Vec<(usize, Packet)> = (2..EXAMPLE.len())
+            .filter(|&i| EXAMPLE[i - 2] + EXAMPLE[i - 1] != EXAMPLE[i])
+            .map(|i| (i, EXAMPLE[i]))
+            .collect();
+        assert_eq!(invalid_numbers(&EXAMPLE, 2), expected);
+    }
+
+    #[test]
+    fn test_validator() {
+        let mut validator = Validator::new(3);
+        assert!(validator.push(1));
+        assert!(validator.push(1));
+        assert!(validator.push(5));
+        // the two 1s are different numbers
+        assert!(validator.push(2));
+        // 1 + 1 left the window with the first 1
+        assert!(!validator.push(2));
+        assert!(validator.push(7));
+    }
+
+    #[test]
+    fn test_contiguous_range() {
+        assert_eq!(contiguous_range(&EXAMPLE, 127), Some(2..6));
+        assert_eq!(weakness(&EXAMPLE, 127), Some(62));
+        // a single number is not a range
+        assert_eq!(contiguous_range(&[1, 5, 2], 5), None);
+        assert_eq!(contiguous_range(&[1, 5, 0, 2], 5), Some(1..3));
+    }
+}
@@ -4,6 +4,8 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+use ocr::OcrError;
+
 type Point = [u32; 2];
 
 #[derive(Debug, Clone, Copy)]
@@ -91,47 +93,44 @@ fn parse_point(line: &str) -> Point {
     ]
 }
 
-fn part1() {
-    let input = read_input("input.txt");
+fn parse_input(input: &str) -> (HashSet<Point>, Vec<Fold>) {
     let mut lines = input.lines();
 
-    let mut points: HashSet<Point> = HashSet::new();
+    let points = lines
+        .by_ref()
+        .take_while(|line| !line.is_empty())
+        .map(parse_point)
+        .collect();
+    let folds = lines.map(parse_instruction).collect();
 
-    for line in lines.by_ref() {
-        if line == "" {
-            break;
-        }
+    (points, folds)
+}
 
-        points.insert(parse_point(line));
-    }
+fn read_message(points: &HashSet<Point>) -> Result<String, OcrError> {
+    ocr::recognize_points(points.iter().map(|&[x, y]| (x.into(), y.into())))
+}
 
-    for line in lines {
-        points = fold(&points, parse_instruction(line));
-        break;
-    }
+fn part1() {
+    let (points, folds) = parse_input(&read_input("input.txt"));
+    let points = fold(&points, folds[0]);
 
     println!("{}", points.len());
 }
 
 fn part2() {
-    let input = read_input("input.txt");
-    let mut lines = input.lines();
-
-    let mut points: HashSet<Point> = HashSet::new();
-
-    for line in lines.by_ref() {
-        if line == "" {
-            break;
-        }
+    let (mut points, folds) = parse_input(&read_input("input.txt"));
 
-        points.insert(parse_point(line));
+    for f in folds {
+        points = fold(&points, f);
     }
 
-    for line in lines {
-        points = fold(&points, parse_instruction(line));
+    match read_message(&points) {
+        Ok(message) => println!("{}", message),
+        Err(err) => {
+            println!("Could not read the code ({}):", err);
+            print_points(&points);
+        }
     }
-
-    print_points(&points);
 }
 
 fn main() {
@@ -145,6 +144,36 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "6,10\n0,14\n9,10\n0,3\n10,4\n4,11\n6,0\n6,12\n4,1\n0,13\n10,12\n3,4\n3,0\n8,4\n1,10\n2,14\n8,10\n9,0\n\nfold along y=7\nfold along x=5";
+
+    #[test]
+    fn test_example() {
+        let (mut points, folds) = parse_input(EXAMPLE);
+        assert_eq!(folds.len(), 2);
+        assert_eq!(fold(&points, folds[0]).len(), 17);
+
+        for f in folds {
+            points = fold(&points, f);
+        }
+        // the example draws a square, which is not a letter
+        assert_eq!(read_message(&points), Err(OcrError::UnknownHeight(5)));
+    }
+
     #[test]
-    fn test_() {}
+    fn test_read_message() {
+        let mut points = HashSet::new();
+        for (y, row) in [".##.", "#..#", "#..#", "####", "#..#", "#..#"]
+            .iter()
+            .enumerate()
+        {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    points.insert([x as u32 + 3, y as u32]);
+                }
+            }
+        }
+        assert_eq!(read_message(&points), Ok("A".to_string()));
+    }
 }
@@ -0,0 +1,396 @@
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+// Nodes along a cycle, the first one repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<K>(pub Vec<K>);
+
+// Directed graph with nodes keyed by any hashable id and edges carrying a
+// weight. Nothing stops cycles from being added, but every ordering query
+// reports them.
+#[derive(Clone, Debug)]
+pub struct Dag<K, E = ()> {
+    ids: Vec<K>,
+    index: HashMap<K, usize>,
+    children: Vec<Vec<(usize, E)>>,
+    parents: Vec<Vec<usize>>,
+}
+
+impl<K, E> Default for Dag<K, E> {
+    fn default() -> Self {
+        Dag {
+            ids: Vec::new(),
+            index: HashMap::new(),
+            children: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> Dag<K, ()> {
+    pub fn from_edges<I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = (K, K)>,
+    {
+        let mut dag = Dag::new();
+        for (from, to) in edges {
+            dag.add_edge(from, to, ());
+        }
+        dag
+    }
+}
+
+impl<K: Eq + Hash + Clone, E> Dag<K, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    // Lookups take anything the keys borrow as, such as a `&str` for
+    // `String` keys.
+    pub fn contains<Q>(&self, node: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(node)
+    }
+
+    // Nodes in the order they were first added.
+    pub fn nodes(&self) -> impl Iterator<Item = &K> {
+        self.ids.iter()
+    }
+
+    pub fn add_node(&mut self, node: K) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        self.ids.push(node.clone());
+        self.children.push(Vec::new());
+        self.parents.push(Vec::new());
+        self.index.insert(node, self.ids.len() - 1);
+        self.ids.len() - 1
+    }
+
+    // Adding the same edge twice only updates its weight.
+    pub fn add_edge(&mut self, from: K, to: K, weight: E) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        match self.children[from]
+            .iter_mut()
+            .find(|(child, _)| *child == to)
+        {
+            Some(edge) => edge.1 = weight,
+            None => {
+                self.children[from].push((to, weight));
+                self.parents[to].push(from);
+            }
+        }
+    }
+
+    pub fn children<'a, Q>(&'a self, node: &Q) -> impl Iterator<Item = (&'a K, &'a E)> + 'a
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let edges = self.index.get(node).map_or(&[][..], |&i| &self.children[i]);
+        edges
+            .iter()
+            .map(move |(child, weight)| (&self.ids[*child], weight))
+    }
+
+    pub fn parents<'a, Q>(&'a self, node: &Q) -> impl Iterator<Item = &'a K> + 'a
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let parents = self.index.get(node).map_or(&[][..], |&i| &self.parents[i]);
+        parents.iter().map(move |&parent| &self.ids[parent])
+    }
+
+    fn reachable<Q>(&self, node: &Q, next: impl Fn(usize) -> Vec<usize>) -> HashSet<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut seen = HashSet::new();
+        let mut to_visit: Vec<usize> = self.index.get(node).into_iter().copied().collect();
+        while let Some(i) = to_visit.pop() {
+            for n in next(i) {
+                if seen.insert(n) {
+                    to_visit.push(n);
+                }
+            }
+        }
+        seen.into_iter().map(|i| &self.ids[i]).collect()
+    }
+
+    // Every node reachable from `node`, not including itself unless it is on
+    // a cycle.
+    pub fn descendants<Q>(&self, node: &Q) -> HashSet<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reachable(node, |i| self.children[i].iter().map(|e| e.0).collect())
+    }
+
+    // Every node `node` can be reached from.
+    pub fn ancestors<Q>(&self, node: &Q) -> HashSet<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reachable(node, |i| self.parents[i].clone())
+    }
+
+    pub fn find_cycle(&self) -> Option<Cycle<K>> {
+        let mut finished = vec![false; self.len()];
+        for root in 0..self.len() {
+            if finished[root] {
+                continue;
+            }
+            // depth-first, with how many children were already visited
+            let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+            while let Some((node, visited)) = path.last_mut() {
+                let node = *node;
+                match self.children[node].get(*visited) {
+                    Some(&(next, _)) => {
+                        *visited += 1;
+                        if let Some(start) = path.iter().position(|&(n, _)| n == next) {
+                            let mut cycle: Vec<K> = path[start..]
+                                .iter()
+                                .map(|&(n, _)| self.ids[n].clone())
+                                .collect();
+                            cycle.push(self.ids[next].clone());
+                            return Some(Cycle(cycle));
+                        }
+                        if !finished[next] {
+                            path.push((next, 0));
+                        }
+                    }
+                    None => {
+                        finished[node] = true;
+                        path.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Kahn's algorithm, always picking the available node with the smallest
+    // key next.
+    pub fn toposort_by_key<P, F>(&self, key: F) -> Result<Vec<K>, Cycle<K>>
+    where
+        P: Ord,
+        F: Fn(&K) -> P,
+    {
+        let mut waiting_on: Vec<usize> = self.parents.iter().map(Vec::len).collect();
+        let mut available: BinaryHeap<Reverse<(P, usize)>> = (0..self.len())
+            .filter(|&i| waiting_on[i] == 0)
+            .map(|i| Reverse((key(&self.ids[i]), i)))
+            .collect();
+
+        let mut ordered = Vec::with_capacity(self.len());
+        while let Some(Reverse((_, i))) = available.pop() {
+            for &(child, _) in &self.children[i] {
+                waiting_on[child] -= 1;
+                if waiting_on[child] == 0 {
+                    available.push(Reverse((key(&self.ids[child]), child)));
+                }
+            }
+            ordered.push(self.ids[i].clone());
+        }
+
+        match self.find_cycle() {
+            Some(cycle) if ordered.len() < self.len() => Err(cycle),
+            _ => Ok(ordered),
+        }
+    }
+
+    // Every topological order. There can be up to n! of them, so this is
+    // only meant for small graphs.
+    pub fn all_toposorts(&self) -> Result<Vec<Vec<K>>, Cycle<K>> {
+        if let Some(cycle) = self.find_cycle() {
+            return Err(cycle);
+        }
+        let mut waiting_on: Vec<usize> = self.parents.iter().map(Vec::len).collect();
+        let mut used = vec![false; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        let mut orders = Vec::new();
+        self.extend_orders(&mut waiting_on, &mut used, &mut order, &mut orders);
+        Ok(orders)
+    }
+
+    fn extend_orders(
+        &self,
+        waiting_on: &mut [usize],
+        used: &mut [bool],
+        order: &mut Vec<usize>,
+        orders: &mut Vec<Vec<K>>,
+    ) {
+        if order.len() == self.len() {
+            orders.push(order.iter().map(|&i| self.ids[i].clone()).collect());
+            return;
+        }
+        for i in 0..self.len() {
+            if used[i] || waiting_on[i] > 0 {
+                continue;
+            }
+            used[i] = true;
+            order.push(i);
+            for &(child, _) in &self.children[i] {
+                waiting_on[child] -= 1;
+            }
+            self.extend_orders(waiting_on, used, order, orders);
+            for &(child, _) in &self.children[i] {
+                waiting_on[child] += 1;
+            }
+            order.pop();
+            used[i] = false;
+        }
+    }
+
+    // Longest chain of nodes by total duration, i.e. the least time needed to
+    // go through every node with unlimited parallelism.
+    pub fn critical_path<F>(&self, duration: F) -> Result<(u64, Vec<K>), Cycle<K>>
+    where
+        F: Fn(&K) -> u64,
+    {
+        let order = self.toposort_by_key(|_| ())?;
+        // (time at which each node is done, previous node on its chain)
+        let mut done: Vec<(u64, Option<usize>)> = vec![(0, None); self.len()];
+        for node in &order {
+            let i = self.index[node];
+            let (start, prev) = self.parents[i]
+                .iter()
+                .map(|&p| (done[p].0, Some(p)))
+                .max()
+                .unwrap_or((0, None));
+            done[i] = (start + duration(node), prev);
+        }
+
+        let mut last = match (0..self.len()).max_by_key(|&i| done[i].0) {
+            Some(last) => last,
+            None => return Ok((0, Vec::new())),
+        };
+        let length = done[last].0;
+        let mut path = vec![self.ids[last].clone()];
+        while let Some(prev) = done[last].1 {
+            path.push(self.ids[prev].clone());
+            last = prev;
+        }
+        path.reverse();
+        Ok((length, path))
+    }
+}
+
+impl<K: Eq + Hash + Clone + Ord, E> Dag<K, E> {
+    // Topological order taking the smallest available node first.
+    pub fn toposort(&self) -> Result<Vec<K>, Cycle<K>> {
+        self.toposort_by_key(K::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Dag<char> {
+        let edges = ["CA", "CF", "AB", "AD", "BE", "DE", "FE"];
+        Dag::from_edges(edges.iter().map(|e| {
+            let mut chars = e.chars();
+            (chars.next().unwrap(), chars.next().unwrap())
+        }))
+    }
+
+    #[test]
+    fn test_toposort() {
+        let dag = example();
+        assert_eq!(dag.toposort(), Ok("CABDFE".chars().collect()));
+        // largest first
+        assert_eq!(
+            dag.toposort_by_key(|&c| Reverse(c)),
+            Ok("CFADBE".chars().collect())
+        );
+    }
+
+    #[test]
+    fn test_all_toposorts() {
+        let orders = example().all_toposorts().unwrap();
+        let orders: HashSet<String> = orders.iter().map(|o| o.iter().collect()).collect();
+        // F can go anywhere after C, and B and D either way
+        assert_eq!(orders.len(), 8);
+        assert!(orders.contains("CFADBE"));
+        assert!(orders.contains("CABDFE"));
+        assert!(!orders.contains("ACBDFE"));
+    }
+
+    #[test]
+    fn test_critical_path() {
+        let duration = |&c: &char| (c as u8 - b'A' + 1) as u64;
+        assert_eq!(
+            example().critical_path(duration),
+            Ok((3 + 6 + 5, "CFE".chars().collect()))
+        );
+        assert_eq!(
+            Dag::<char>::new().critical_path(duration),
+            Ok((0, Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut dag = example();
+        assert_eq!(dag.find_cycle(), None);
+        dag.add_edge('E', 'A', ());
+        let cycle = Cycle("ABEA".chars().collect());
+        assert_eq!(dag.find_cycle(), Some(Cycle("ABEA".chars().collect())));
+        assert_eq!(dag.toposort(), Err(cycle));
+        assert!(dag.all_toposorts().is_err());
+
+        let mut dag = Dag::new();
+        dag.add_edge("loop", "loop", ());
+        assert_eq!(dag.find_cycle(), Some(Cycle(vec!["loop", "loop"])));
+    }
+
+    #[test]
+    fn test_weights_and_reachability() {
+        let mut dag: Dag<&str, u64> = Dag::new();
+        dag.add_edge("light red", "bright white", 1);
+        dag.add_edge("light red", "muted yellow", 2);
+        dag.add_edge("bright white", "shiny gold", 1);
+        dag.add_edge("muted yellow", "shiny gold", 2);
+        dag.add_edge("muted yellow", "shiny gold", 3);
+
+        assert_eq!(dag.len(), 4);
+        assert_eq!(
+            dag.children(&"muted yellow").collect::<Vec<_>>(),
+            vec![(&"shiny gold", &3)]
+        );
+        assert_eq!(dag.parents(&"shiny gold").count(), 2);
+        assert_eq!(dag.ancestors(&"shiny gold").len(), 3);
+        assert_eq!(dag.descendants(&"light red").len(), 3);
+        assert!(dag.descendants(&"faded blue").is_empty());
+    }
+
+    #[test]
+    fn test_borrowed_lookups() {
+        let mut dag: Dag<String> = Dag::new();
+        dag.add_edge("a".to_string(), "b".to_string(), ());
+        assert!(dag.contains("a"));
+        assert_eq!(dag.children("a").count(), 1);
+        assert_eq!(dag.parents("b").count(), 1);
+        assert_eq!(dag.ancestors("b").len(), 1);
+        assert_eq!(dag.descendants("a").len(), 1);
+    }
+}
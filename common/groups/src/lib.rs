@@ -0,0 +1,35 @@
+// Lines of the input, grouped at blank lines. Any number of blank lines
+// separates two groups, and there are no empty groups.
+pub fn groups(s: &str) -> Vec<Vec<&str>> {
+    let mut groups = vec![Vec::new()];
+    for line in s.lines() {
+        if line.trim().is_empty() {
+            if !groups.last().unwrap().is_empty() {
+                groups.push(Vec::new());
+            }
+        } else {
+            groups.last_mut().unwrap().push(line);
+        }
+    }
+    if groups.last().unwrap().is_empty() {
+        groups.pop();
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups() {
+        assert_eq!(
+            groups("abc\n\na\nb\nc\n\nab\nac\n"),
+            vec![vec!["abc"], vec!["a", "b", "c"], vec!["ab", "ac"]]
+        );
+        // leading, repeated and whitespace-only separators
+        assert_eq!(groups("\na\n\n \n\nb\n\n"), vec![vec!["a"], vec!["b"]]);
+        assert!(groups("").is_empty());
+        assert!(groups("\n\n").is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+
+// The 4x6 font used since 2016 (some glyphs are narrower or wider than 4).
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// The 6x10 font used in 2018.
+#[rustfmt::skip]
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OcrError {
+    Empty,
+    // only 6 and 10 rows tall messages are known
+    UnknownHeight(usize),
+    // index of the glyph in the message, and its drawing
+    UnknownGlyph(usize, String),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "nothing to read"),
+            OcrError::UnknownHeight(h) => write!(f, "no font is {} rows tall", h),
+            OcrError::UnknownGlyph(i, glyph) => write!(f, "unknown glyph #{}:\n{}", i, glyph),
+        }
+    }
+}
+
+type Bitmap = Vec<Vec<bool>>;
+
+fn parse_rows<'a, I>(rows: I) -> Bitmap
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    rows.into_iter()
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+fn column_is_blank(bitmap: &[Vec<bool>], col: usize) -> bool {
+    bitmap
+        .iter()
+        .all(|row| !row.get(col).copied().unwrap_or(false))
+}
+
+// Drops blank rows at the top and bottom and blank columns on both sides.
+fn trim(bitmap: &[Vec<bool>]) -> Bitmap {
+    let lit_rows: Vec<usize> = (0..bitmap.len())
+        .filter(|&r| bitmap[r].iter().any(|&lit| lit))
+        .collect();
+    let width = bitmap.iter().map(Vec::len).max().unwrap_or(0);
+    let lit_cols: Vec<usize> = (0..width)
+        .filter(|&c| !column_is_blank(bitmap, c))
+        .collect();
+
+    match (
+        lit_rows.first(),
+        lit_rows.last(),
+        lit_cols.first(),
+        lit_cols.last(),
+    ) {
+        (Some(&top), Some(&bot), Some(&left), Some(&right)) => bitmap[top..=bot]
+            .iter()
+            .map(|row| {
+                (left..=right)
+                    .map(|c| row.get(c).copied().unwrap_or(false))
+                    .collect()
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn draw(bitmap: &[Vec<bool>]) -> String {
+    bitmap
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn find_glyph<const H: usize>(font: &[(char, [&str; H])], glyph: &[Vec<bool>]) -> Option<char> {
+    font.iter()
+        .find(|(_, rows)| trim(&parse_rows(rows.iter().copied())) == glyph)
+        .map(|&(c, _)| c)
+}
+
+fn recognize_bitmap(bitmap: &[Vec<bool>]) -> Result<String, OcrError> {
+    let bitmap = trim(bitmap);
+    let height = bitmap.len();
+    if height == 0 {
+        return Err(OcrError::Empty);
+    }
+    let width = bitmap[0].len();
+
+    // glyphs are separated by at least one blank column
+    let mut glyphs = Vec::new();
+    let mut start = None;
+    for col in 0..=width {
+        match (start, col < width && !column_is_blank(&bitmap, col)) {
+            (None, true) => start = Some(col),
+            (Some(left), false) => {
+                glyphs.push(trim(
+                    &bitmap
+                        .iter()
+                        .map(|row| row[left..col].to_vec())
+                        .collect::<Bitmap>(),
+                ));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    glyphs
+        .iter()
+        .enumerate()
+        .map(|(i, glyph)| {
+            let found = match height {
+                6 => find_glyph(&SMALL_FONT, glyph),
+                10 => find_glyph(&LARGE_FONT, glyph),
+                _ => return Err(OcrError::UnknownHeight(height)),
+            };
+            found.ok_or_else(|| OcrError::UnknownGlyph(i, draw(glyph)))
+        })
+        .collect()
+}
+
+// Reads a message drawn with '#' for lit pixels and anything else for blanks.
+pub fn recognize_grid(s: &str) -> Result<String, OcrError> {
+    recognize_bitmap(&parse_rows(s.lines()))
+}
+
+pub fn recognize_points<I>(points: I) -> Result<String, OcrError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let points: Vec<(i64, i64)> = points.into_iter().collect();
+    let (min_x, min_y) = match (
+        points.iter().map(|p| p.0).min(),
+        points.iter().map(|p| p.1).min(),
+    ) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return Err(OcrError::Empty),
+    };
+    let max_x = points.iter().map(|p| p.0).max().unwrap();
+    let max_y = points.iter().map(|p| p.1).max().unwrap();
+
+    let mut bitmap = vec![vec![false; (max_x - min_x + 1) as usize]; (max_y - min_y + 1) as usize];
+    for (x, y) in points {
+        bitmap[(y - min_y) as usize][(x - min_x) as usize] = true;
+    }
+
+    recognize_bitmap(&bitmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_font() {
+        let grid = "\
+..##.###..####..##..#..#..##..#..#.###.
+...#.#..#....#.#..#.#..#.#..#.#..#.#..#
+...#.#..#...#..#....#..#.#..#.#..#.#..#
+...#.###...#...#....#..#.####.#..#.###.
+#..#.#....#....#..#.#..#.#..#.#..#.#.#.
+.##..#....####..##...##..#..#..##..#..#";
+        assert_eq!(recognize_grid(grid), Ok("JPZCUAUR".to_string()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let grid = "\
+.####...#####...#....#..#....#..######..######..#####...######
+#....#..#....#..#....#..#...#...#............#..#....#.......#
+#.......#....#...#..#...#..#....#............#..#....#.......#
+#.......#....#...#..#...#.#.....#...........#...#....#......#.
+#.......#####.....##....##......#####......#....#####......#..
+#.......#..#......##....##......#.........#.....#.........#...
+#.......#...#....#..#...#.#.....#........#......#........#....
+#.......#...#....#..#...#..#....#.......#.......#.......#.....
+#....#..#....#..#....#..#...#...#.......#.......#.......#.....
+.####...#....#..#....#..#....#..######..######..#.......######";
+        assert_eq!(recognize_grid(grid), Ok("CRXKEZPZ".to_string()));
+    }
+
+    #[test]
+    fn test_every_glyph() {
+        let small = (0..6)
+            .map(|r| {
+                SMALL_FONT
+                    .iter()
+                    .map(|(_, rows)| rows[r])
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let expected: String = SMALL_FONT.iter().map(|(c, _)| c).collect();
+        assert_eq!(recognize_grid(&small), Ok(expected));
+    }
+
+    #[test]
+    fn test_points() {
+        // an 'L' somewhere far from the origin
+        let points = (0..6)
+            .map(|y| (-10, 100 + y))
+            .chain((1..4).map(|x| (-10 + x, 105)));
+        assert_eq!(recognize_points(points), Ok("L".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(recognize_grid("...\n..."), Err(OcrError::Empty));
+        assert_eq!(recognize_points(vec![]), Err(OcrError::Empty));
+        assert_eq!(recognize_grid("#\n#\n#"), Err(OcrError::UnknownHeight(3)));
+        assert_eq!(
+            recognize_grid("#..#.####\n#..#.#..#\n####.#..#\n#..#.#..#\n#..#.#..#\n#..#.####"),
+            Err(OcrError::UnknownGlyph(
+                1,
+                "####\n#..#\n#..#\n#..#\n#..#\n####".to_string()
+            ))
+        );
+    }
+}